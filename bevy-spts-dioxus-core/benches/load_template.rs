@@ -0,0 +1,152 @@
+//! Benchmarks spawning a list of 10,000 items, which loads the same template for every item,
+//! and a list of 1,000 instances of a 111 node template, where converting the template is a
+//! larger share of the work.
+//!
+//! Uses a hand written adapter as the `#[bevy_spts_dioxus]` macro can't be used from the core
+//! crate.
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::BuildChildren;
+use bevy_spts_dioxus_core::prelude::*;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+#[allow(non_camel_case_types, non_upper_case_globals)]
+mod dioxus_elements {
+    pub type AttributeDescription = (&'static str, Option<&'static str>, bool);
+    const NAME_SPACE: Option<&'static str> = Some("bench");
+
+    pub mod elements {
+        pub struct item;
+        impl item {
+            pub const TAG_NAME: &'static str = "item";
+        }
+    }
+
+    pub struct item;
+    impl item {
+        pub const NAME_SPACE: Option<&'static str> = NAME_SPACE;
+        pub const index: AttributeDescription = ("index", None, false);
+        pub const label: AttributeDescription = ("label", None, false);
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Component)]
+struct Index(i64);
+
+#[derive(Debug, Clone)]
+enum BenchNode {
+    Item {
+        /// Static attributes, converted like the generated adapters do.
+        #[allow(dead_code)]
+        attributes: Vec<(&'static str, String)>,
+        children: Box<[Self]>,
+    },
+    Text,
+    Dynamic,
+}
+
+impl SptsDioxusTemplateNode for BenchNode {
+    fn from_dioxus(node: &TemplateNode) -> Self {
+        match node {
+            TemplateNode::Element {
+                attrs, children, ..
+            } => Self::Item {
+                attributes: attrs
+                    .iter()
+                    .filter_map(|attr| match attr {
+                        TemplateAttribute::Static { name, value, .. } => {
+                            Some((*name, value.to_string()))
+                        }
+                        TemplateAttribute::Dynamic { .. } => None,
+                    })
+                    .collect(),
+                children: children.iter().map(Self::from_dioxus).collect(),
+            },
+            TemplateNode::Text { .. } => Self::Text,
+            TemplateNode::Dynamic { .. } => Self::Dynamic,
+        }
+    }
+
+    fn spawn(&self, world: &mut World) -> Entity {
+        match self {
+            Self::Item { children, .. } => {
+                let children = children
+                    .iter()
+                    .map(|child| child.spawn(world))
+                    .collect::<Box<[_]>>();
+                world.spawn(Index(0)).add_children(&children).id()
+            }
+            Self::Text | Self::Dynamic => world.spawn_empty().id(),
+        }
+    }
+
+    fn apply_attribute(world: &mut World, entity: Entity, _name: &'static str, value: &dioxus_core::AttributeValue) {
+        world.entity_mut(entity).insert(Index(value.as_i64().unwrap_or_default()));
+    }
+}
+
+#[component]
+fn list() -> Element {
+    rsx! {
+        for i in 0..10_000 {
+            item {
+                index: i,
+                item {}
+                item {}
+            }
+        }
+    }
+}
+
+#[component]
+fn large_template_list() -> Element {
+    rsx! {
+        for i in 0..1_000 {
+            item {
+                index: i,
+                label: "root",
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+                item { label: "branch", item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } item { label: "leaf" } }
+            }
+        }
+    }
+}
+
+fn spawn(root: fn() -> Element) -> impl FnMut(&mut criterion::Bencher) {
+    move |b| {
+        b.iter_batched(
+            || {
+                let mut app = App::new();
+                app.add_plugins(SptsDioxusPlugin::<BenchNode>::default());
+                app.world_mut().spawn(SptsDioxusRootComponent::new(root));
+                app
+            },
+            |mut app| app.update(),
+            BatchSize::PerIteration,
+        );
+    }
+}
+
+fn spawn_list(c: &mut Criterion) {
+    c.bench_function("spawn list of 10,000 items", spawn(list));
+    c.bench_function(
+        "spawn list of 1,000 instances of a 111 node template",
+        spawn(large_template_list),
+    );
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = spawn_list
+}
+criterion_main!(benches);
@@ -1,16 +1,89 @@
 use std::{any::Any, fmt::Debug, rc::Rc};
 
+use bevy_app::App;
+use bevy_derive::Deref;
 use bevy_ecs::{
+    component::Component,
     entity::Entity,
+    system::{Query, Resource, SystemState},
     world::{EntityWorldMut, World},
 };
+use bevy_hierarchy::{Children, HierarchyQueryExt};
+use bevy_utils::HashMap;
 use dioxus::{dioxus_core::AttributeValue, prelude::{IntoAttributeValue, TemplateNode}};
 
+use crate::error::SptsDioxusErrorKind;
+
 /// Intermediary format from Dioxus's [Template] that can be spawned into the world.
 pub trait SptsDioxusTemplateNode: Send + Debug + Sync + Clone + 'static {
     fn from_dioxus(node: &TemplateNode) -> Self;
     fn spawn(&self, world: &mut World) -> Entity;
     fn apply_attribute(world: &mut World, entity: Entity, name: &'static str, value: &AttributeValue);
+    /// Called instead of [SptsDioxusTemplateNode::apply_attribute] when dioxus removes an
+    /// attribute (sets it to `AttributeValue::None`).
+    fn remove_attribute(world: &mut World, entity: Entity, name: &'static str) {
+        Self::apply_attribute(world, entity, name, &AttributeValue::None);
+    }
+    /// Registers the bevy events that are forwarded to the elements' dioxus event listeners.
+    fn register_events(_app: &mut App) {}
+
+    /// Spawns the entity for a text node, i.e. a bare string in rsx.
+    fn spawn_text(world: &mut World, text: &str) -> Entity {
+        world.spawn(SptsDioxusText(text.to_string())).id()
+    }
+    /// Updates the text of an entity spawned with [SptsDioxusTemplateNode::spawn_text].
+    fn set_text(world: &mut World, entity: Entity, text: &str) {
+        world.entity_mut(entity).insert(SptsDioxusText(text.to_string()));
+    }
+    /// Spawns the entity that holds the place of a node that isn't rendered (i.e. an empty list)
+    /// or of a template's dynamic node until it's filled.  The default entity only has a
+    /// [SptsDioxusPlaceholder], without a `Transform` or `Visibility` it's skipped by transform
+    /// and visibility propagation.  Override it to i.e. add a `Name` for the inspector.
+    fn spawn_placeholder(world: &mut World) -> Entity {
+        world.spawn(SptsDioxusPlaceholder).id()
+    }
+
+    // Fallible variants used by the `MutationApplier`, errors are handled according to the
+    // `SptsDioxusErrorPolicy`.  Default to the infallible methods above.
+
+    fn try_from_dioxus(node: &TemplateNode) -> Result<Self, SptsDioxusErrorKind> {
+        Ok(Self::from_dioxus(node))
+    }
+    /// Should not leave any entities behind if it fails.
+    fn try_spawn(&self, world: &mut World) -> Result<Entity, SptsDioxusErrorKind> {
+        Ok(self.spawn(world))
+    }
+    /// Like [SptsDioxusTemplateNode::try_spawn], also pushes the entity spawned for each template
+    /// node onto `nodes` in depth first order (a node before its children) so they can be
+    /// matched with the dioxus template.  Defaults to the spawned entity and its descendants.
+    fn try_spawn_nodes(
+        &self,
+        world: &mut World,
+        nodes: &mut Vec<Entity>,
+    ) -> Result<Entity, SptsDioxusErrorKind> {
+        let entity = self.try_spawn(world)?;
+        nodes.push(entity);
+        let mut children: SystemState<Query<&Children>> = SystemState::new(world);
+        nodes.extend(children.get(world).iter_descendants_depth_first(entity));
+        Ok(entity)
+    }
+    fn try_apply_attribute(
+        world: &mut World,
+        entity: Entity,
+        name: &'static str,
+        value: &AttributeValue,
+    ) -> Result<(), SptsDioxusErrorKind> {
+        Self::apply_attribute(world, entity, name, value);
+        Ok(())
+    }
+    fn try_remove_attribute(
+        world: &mut World,
+        entity: Entity,
+        name: &'static str,
+    ) -> Result<(), SptsDioxusErrorKind> {
+        Self::remove_attribute(world, entity, name);
+        Ok(())
+    }
 }
 
 #[allow(dead_code)]
@@ -103,9 +176,66 @@ impl<T: Any + PartialEq> IntoAttributeValue for WA<T> {
     }
 }
 
+/// Tag name of the element an entity was spawned from, used to namespace attributes per element.
+#[derive(Component, Deref, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SptsDioxusElementTag(pub &'static str);
+
+/// Text of a text node when the adapter doesn't map text nodes to another component.
+#[derive(Component, Deref, Debug, Clone, PartialEq, Eq)]
+pub struct SptsDioxusText(pub String);
+
+/// Marks the entities spawned with [SptsDioxusTemplateNode::spawn_placeholder].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SptsDioxusPlaceholder;
+
 /// Implement this trait on a #\[define_element\] struct to spawn it.
 pub trait SptsDioxusElement {
-    fn spawn(world: &mut World) -> EntityWorldMut {
+    fn spawn(world: &mut World) -> EntityWorldMut<'_> {
         world.spawn_empty()
     }
 }
+
+/// Values that the `#[component]` fields had when their element was first spawned, keyed on the
+/// element's tag and the field's name.
+#[derive(Resource, Default)]
+struct SpawnedComponents(HashMap<(&'static str, &'static str), Box<dyn Any + Send + Sync>>);
+
+/// Records the value of `C` that `entity` was spawned with, unless one was already recorded for
+/// the `tag` element's `name` field.  Called by the code generated for `#[component]` fields when
+/// an element is spawned.
+pub fn record_spawned_component<C: Component + Clone>(
+    world: &mut World,
+    entity: Entity,
+    tag: &'static str,
+    name: &'static str,
+) {
+    let recorded = world
+        .get_resource::<SpawnedComponents>()
+        .is_some_and(|spawned| spawned.0.contains_key(&(tag, name)));
+    if recorded {
+        return;
+    }
+    let Some(value) = world.get::<C>(entity).cloned() else {
+        return;
+    };
+    world
+        .get_resource_or_insert_with(SpawnedComponents::default)
+        .0
+        .insert((tag, name), Box::new(value));
+}
+
+/// Value of `C` recorded with [record_spawned_component] for the `tag` element's `name` field,
+/// falling back to `C::default()`.  Called by the code generated for `#[component]` fields to
+/// reset them when their attribute is removed.
+pub fn spawned_component<C: Component + Clone + Default>(
+    world: &World,
+    tag: &'static str,
+    name: &'static str,
+) -> C {
+    world
+        .get_resource::<SpawnedComponents>()
+        .and_then(|spawned| spawned.0.get(&(tag, name)))
+        .and_then(|value| value.downcast_ref::<C>())
+        .cloned()
+        .unwrap_or_default()
+}
@@ -1,35 +1,157 @@
-use crate::{adapter::SptsDioxusTemplateNode, ecs_hooks::EcsContext};
-use bevy_ecs::system::{IntoSystem, Resource, System};
+use std::{any::TypeId, mem, rc::Rc};
 
-#[derive(Resource, Default)]
+use crate::{
+    adapter::SptsDioxusTemplateNode,
+    ecs_hooks::{EcsContext, WorldScope},
+};
+use bevy_ecs::{
+    entity::Entity,
+    system::{In, IntoSystem, RunSystemOnce, SystemId, SystemInput},
+    world::World,
+};
+use bevy_utils::{tracing::warn, HashMap};
+use dioxus::{
+    dioxus_core::prelude::{Runtime, RuntimeGuard},
+    signals::{Signal, Writable},
+};
+
+type DeferredSystemRun = Box<dyn FnOnce(&mut World)>;
+
+/// Systems scheduled by [DeferredSystemScheduler], run at the start of the next
+/// `tick_dioxus_ui`.  It's a non-send resource as the runs hold the signals that their results are
+/// written to.
+#[derive(Default)]
 pub struct DeferredSystemRunQueue {
-    pub run_queue: Box<Vec<Box<dyn System<In = (), Out = ()>>>>,
+    run_queue: Vec<DeferredSystemRun>,
+    /// Entities of the registered (zero sized) systems keyed on the system's type, so they're only
+    /// initialized once.
+    cached_systems: HashMap<TypeId, Entity>,
+}
+
+impl DeferredSystemRunQueue {
+    pub(crate) fn run_queued(world: &mut World) {
+        let run_queue = mem::take(&mut world.non_send_resource_mut::<Self>().run_queue);
+        for run in run_queue {
+            run(world);
+        }
+    }
+}
+
+/// Runs `system`, systems that capture state (i.e. closures) can't be told apart by their type so
+/// they're initialized on every run.
+fn run_system<I, O, M, S>(world: &mut World, system: S, input: I::Inner<'static>) -> Option<O>
+where
+    I: SystemInput + 'static,
+    O: 'static,
+    S: IntoSystem<I, O, M> + 'static,
+{
+    if size_of::<S>() != 0 {
+        return world
+            .run_system_once_with(input, system)
+            .map_err(|err| warn!("bevy_spts_dioxus: Couldn't run deferred system. {err}"))
+            .ok();
+    }
+
+    let type_id = TypeId::of::<S::System>();
+    let cached = world
+        .non_send_resource::<DeferredSystemRunQueue>()
+        .cached_systems
+        .get(&type_id)
+        .copied()
+        .filter(|entity| world.get_entity(*entity).is_ok());
+    let system_id = match cached {
+        Some(entity) => SystemId::<I, O>::from_entity(entity),
+        None => {
+            let system_id = world.register_system(system);
+            world
+                .non_send_resource_mut::<DeferredSystemRunQueue>()
+                .cached_systems
+                .insert(type_id, system_id.entity());
+            system_id
+        }
+    };
+    world
+        .run_system_with_input(system_id, input)
+        .map_err(|err| warn!("bevy_spts_dioxus: Couldn't run deferred system. {err}"))
+        .ok()
 }
 
-#[derive(Clone, Copy)]
+/// Handle returned by [use_system_scheduler].
+#[derive(Clone)]
 pub struct DeferredSystemScheduler {
-    run_queue: *mut Vec<Box<dyn System<In = (), Out = ()>>>,
+    world: WorldScope,
+    /// Runtime of the root, results are written to signals within it.
+    runtime: Rc<Runtime>,
 }
 
 impl DeferredSystemScheduler {
+    fn push(&self, run: impl FnOnce(&mut World) + 'static) {
+        self.world.with_world_mut(|world| {
+            world
+                .non_send_resource_mut::<DeferredSystemRunQueue>()
+                .run_queue
+                .push(Box::new(run));
+        });
+    }
+
+    /// Queues `system` to run at the start of the next frame's `tick_dioxus_ui`.
+    ///
+    /// Panics if it's called outside of rendering / event handlers, i.e. from a spawned task.
     pub fn schedule<S, M>(&self, system: S)
     where
         S: IntoSystem<(), (), M> + 'static,
         M: 'static,
     {
-        unsafe { &mut *self.run_queue }.push(Box::new(S::into_system(system)));
+        self.push(move |world| {
+            run_system(world, system, ());
+        });
     }
-}
 
-unsafe impl Send for DeferredSystemScheduler {}
-unsafe impl Sync for DeferredSystemScheduler {}
+    /// Queues `system` to run with `input` at the start of the next frame's `tick_dioxus_ui`, its
+    /// output is written to `result` (re-rendering the scopes that read it).
+    ///
+    /// Panics if it's called outside of rendering / event handlers, i.e. from a spawned task.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn buy(In(item): In<Item>, mut gold: ResMut<Gold>) -> bool { ... }
+    ///
+    /// let scheduler = use_system_scheduler::<SptsDioxusAdapter>();
+    /// let bought = use_signal(|| None);
+    /// rsx! {
+    ///     spatial {
+    ///         onpress: move |_| scheduler.schedule_with(buy, Item::Sword, bought),
+    ///         if bought() == Some(false) {
+    ///             text { "Not enough gold" }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn schedule_with<I, O, S, M>(&self, system: S, input: I, mut result: Signal<Option<O>>)
+    where
+        I: 'static,
+        O: 'static,
+        S: IntoSystem<In<I>, O, M> + 'static,
+        M: 'static,
+    {
+        let runtime = self.runtime.clone();
+        self.push(move |world| {
+            let Some(output) = run_system(world, system, input) else {
+                return;
+            };
+            let _runtime = RuntimeGuard::new(runtime);
+            // The scope that owns the signal may have been dropped since.
+            if let Ok(mut result) = result.try_write() {
+                *result = Some(output);
+            }
+        });
+    }
+}
 
 pub fn use_system_scheduler<TT: SptsDioxusTemplateNode>() -> DeferredSystemScheduler {
     DeferredSystemScheduler {
-        run_queue: Box::as_mut(
-            &mut EcsContext::<TT>::get_world()
-                .resource_mut::<DeferredSystemRunQueue>()
-                .run_queue,
-        ),
+        world: EcsContext::<TT>::get_world_scope(),
+        runtime: Runtime::current().expect("bevy_spts_dioxus: No dioxus runtime is active."),
     }
 }
@@ -1,7 +1,9 @@
-use crate::{adapter::SptsDioxusTemplateNode, SptsDioxusContext};
+use crate::adapter::SptsDioxusTemplateNode;
 use bevy_ecs::{
-    component::ComponentId,
-    query::{QueryFilter, ReadOnlyQueryData},
+    component::{ComponentId, Tick},
+    entity::Entity,
+    event::{Event, EventCursor, Events},
+    query::{QueryFilter, QueryState, ReadOnlyQueryData},
     system::{Query, Resource, SystemState},
     world::World,
 };
@@ -12,14 +14,113 @@ use dioxus::{
     prelude::{consume_context, current_scope_id, use_drop, Callback},
     signals::Memo,
 };
-use std::{any::TypeId, marker::PhantomData};
+use std::{
+    any::TypeId,
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    ptr::NonNull,
+    rc::Rc,
+};
+
+/// Event type to a fn that checks for new events since it was last called + the subscribed scopes.
+type EventSubscriptions = HashMap<TypeId, (Box<dyn Fn(&World) -> bool>, HashSet<ScopeId>)>;
+
+/// Subscription id to the subscribed scope + a fn that checks if what the scope reads (i.e. a
+/// query's result) changed since it was last called.
+type ChangeSubscriptions = HashMap<usize, (ScopeId, Box<dyn FnMut(&World) -> bool>)>;
 
 #[derive(Default)]
 pub(crate) struct EcsSubscriptions {
-    pub resources: Box<HashMap<ComponentId, HashSet<ScopeId>>>,
-    #[allow(clippy::type_complexity)]
-    pub events: Box<HashMap<TypeId, (Box<dyn Fn(&World) -> bool>, HashSet<ScopeId>)>>,
-    pub world_and_queries: Box<HashSet<ScopeId>>,
+    pub resources: HashMap<ComponentId, HashSet<ScopeId>>,
+    pub events: EventSubscriptions,
+    pub changes: ChangeSubscriptions,
+    next_change_id: usize,
+    /// Scopes with access to the whole world, these are re-rendered every frame.
+    pub world: HashSet<ScopeId>,
+    /// Change tick that the resource subscriptions were last checked at.
+    pub last_run: Tick,
+}
+
+/// Shared between the root and the hooks that subscribe, so `use_drop` can unsubscribe after the
+/// root is gone.
+pub(crate) type SharedEcsSubscriptions = Rc<RefCell<EcsSubscriptions>>;
+
+const WORLD_OUT_OF_SCOPE: &str = "bevy_spts_dioxus: The world can only be accessed while the root \
+    is rendering or handling events, not from spawned tasks or after the frame, and not from \
+    within another `with_world_mut` call.";
+
+const WORLD_BORROWED: &str = "bevy_spts_dioxus: The world can't be mutably accessed from within a \
+    `with_world` call (i.e. while running a `UseQuery::query` closure).";
+
+/// Gives a root's hooks access to the world while it's rendering or handling events, see
+/// [WorldScope::scope].  Outside of that the world can't be reached and accessing it panics.
+#[derive(Clone, Default)]
+pub(crate) struct WorldScope(Rc<WorldScopeState>);
+
+#[derive(Default)]
+struct WorldScopeState {
+    world: Cell<Option<NonNull<World>>>,
+    /// Number of [WorldScope::with_world] calls that are running, the world can't be mutably
+    /// accessed while they hold a reference to it.
+    readers: Cell<usize>,
+}
+
+/// Restores the previous pointer of a [WorldScope], even if the closure it was replaced for
+/// panics.
+struct Reset<'a>(&'a Cell<Option<NonNull<World>>>, Option<NonNull<World>>);
+
+impl Drop for Reset<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.1);
+    }
+}
+
+/// Counts a running [WorldScope::with_world] call until it returns or panics.
+struct Reader<'a>(&'a Cell<usize>);
+
+impl<'a> Reader<'a> {
+    fn new(readers: &'a Cell<usize>) -> Self {
+        readers.set(readers.get() + 1);
+        Self(readers)
+    }
+}
+
+impl Drop for Reader<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+
+impl WorldScope {
+    /// Makes `world` accessible through this scope while `f` runs.  `f` can't borrow `world`
+    /// itself so the hooks are the only ones accessing it.
+    pub fn scope<R>(&self, world: &mut World, f: impl FnOnce() -> R) -> R {
+        let _reset = Reset(&self.0.world, self.0.world.replace(Some(NonNull::from(world))));
+        f()
+    }
+
+    /// Calls `f` with the world, panics outside of [WorldScope::scope] or a
+    /// [WorldScope::with_world_mut] call.  The world can't be mutably accessed through the scope
+    /// until `f` returns.
+    pub fn with_world<R>(&self, f: impl FnOnce(&World) -> R) -> R {
+        let world = self.0.world.get().expect(WORLD_OUT_OF_SCOPE);
+        let _reader = Reader::new(&self.0.readers);
+        // SAFETY: The pointer is only set while `scope` exclusively borrows the world,
+        // `with_world_mut` takes it out while it mutably borrows the world and panics while the
+        // reference is borrowed by `f`.
+        f(unsafe { world.as_ref() })
+    }
+
+    /// Calls `f` with the world, panics outside of [WorldScope::scope] or a
+    /// [WorldScope::with_world_mut] call and from within a [WorldScope::with_world] call.  The
+    /// world can't be reached through the scope until `f` returns.
+    pub fn with_world_mut<R>(&self, f: impl FnOnce(&mut World) -> R) -> R {
+        let mut world = self.0.world.get().expect(WORLD_OUT_OF_SCOPE);
+        assert_eq!(self.0.readers.get(), 0, "{WORLD_BORROWED}");
+        let _reset = Reset(&self.0.world, self.0.world.take());
+        // SAFETY: Same as `with_world`, nothing else can reach the pointer until `f` returns.
+        f(unsafe { world.as_mut() })
+    }
 }
 
 /// Struct that has static functions for hooks that use the correct adapter.
@@ -29,123 +130,290 @@ pub struct SptsDioxusHooks<TT: SptsDioxusTemplateNode> {
     pd: PhantomData<TT>,
 }
 
+/// Provided as a root context when the root is created.
 #[derive(Clone)]
 pub(crate) struct EcsContext<TT: SptsDioxusTemplateNode> {
-    pub world: *mut World,
+    pub world: WorldScope,
+    /// Subscriptions of the root.
+    pub subscriptions: SharedEcsSubscriptions,
     pd: PhantomData<TT>,
 }
 
 impl<TT: SptsDioxusTemplateNode> EcsContext<TT> {
-    pub fn new(world: *mut World) -> Self {
+    pub fn new(world: WorldScope, subscriptions: SharedEcsSubscriptions) -> Self {
         Self {
             world,
+            subscriptions,
             pd: PhantomData,
         }
     }
 }
 
 impl<TT: SptsDioxusTemplateNode> EcsContext<TT> {
-    pub fn get_world<'a>() -> &'a mut World {
-        unsafe { &mut *consume_context::<EcsContext<TT>>().world }
+    pub fn get_world_scope() -> WorldScope {
+        consume_context::<EcsContext<TT>>().world
+    }
+
+    /// Panics if the root isn't rendering or handling events.
+    pub fn with_world<R>(f: impl FnOnce(&World) -> R) -> R {
+        Self::get_world_scope().with_world(f)
+    }
+
+    /// Panics if the root isn't rendering or handling events.
+    pub fn with_world_mut<R>(f: impl FnOnce(&mut World) -> R) -> R {
+        Self::get_world_scope().with_world_mut(f)
+    }
+
+    pub fn get_subscriptions() -> SharedEcsSubscriptions {
+        consume_context::<EcsContext<TT>>().subscriptions
     }
 }
 
 impl<TT: SptsDioxusTemplateNode> SptsDioxusHooks<TT> {
-    pub fn use_world<'a>() -> &'a World {
-        let world = EcsContext::<TT>::get_world();
-
+    /// Calls `f` with the world, the component is re-rendered every frame.
+    pub fn use_world<R>(f: impl FnOnce(&World) -> R) -> R {
         let scope_id = current_scope_id().unwrap();
-        let subscription_manager = use_hook(|| {
-            let subscription_manager = &mut world
-                .non_send_resource_mut::<SptsDioxusContext<TT>>()
-                .subscriptions
-                .world_and_queries;
-            subscription_manager.insert(scope_id);
-            Box::as_mut(subscription_manager) as *mut HashSet<ScopeId>
+        let subscriptions = use_hook(|| {
+            let subscriptions = EcsContext::<TT>::get_subscriptions();
+            subscriptions.borrow_mut().world.insert(scope_id);
+            subscriptions
         });
         use_drop(move || {
-            unsafe { &mut *subscription_manager }.remove(&scope_id);
+            subscriptions.borrow_mut().world.remove(&scope_id);
         });
 
-        world
+        EcsContext::<TT>::with_world(f)
     }
 
-    pub fn use_bevy_resource<'a, T: Resource>() -> &'a T {
-        let world = EcsContext::<TT>::get_world();
+    /// Returns a clone of the `T` resource, the component is re-rendered when it changes.
+    pub fn use_bevy_resource<T: Resource + Clone>() -> T {
+        Self::use_resource_subscription::<T>();
+        EcsContext::<TT>::with_world(|world| world.resource::<T>().clone())
+    }
 
-        let resource_id = world.components().resource_id::<T>().unwrap();
+    /// Re-renders the current scope when the `T` resource changes.
+    pub(crate) fn use_resource_subscription<T: Resource>() {
+        let resource_id =
+            EcsContext::<TT>::with_world(|world| world.components().resource_id::<T>().unwrap());
         let scope_id = current_scope_id().unwrap();
-        let subscription_manager = use_hook(|| {
-            let subscription_manager = &mut world
-                .non_send_resource_mut::<SptsDioxusContext<TT>>()
-                .subscriptions
-                .resources;
-            subscription_manager
+        let subscriptions = use_hook(|| {
+            let subscriptions = EcsContext::<TT>::get_subscriptions();
+            subscriptions
+                .borrow_mut()
+                .resources
                 .entry(resource_id)
                 .or_default()
                 .insert(scope_id);
-            Box::as_mut(subscription_manager) as *mut HashMap<ComponentId, HashSet<ScopeId>>
+            subscriptions
         });
         use_drop(move || {
-            let subscription_manager = &mut unsafe { &mut *subscription_manager };
+            let subscription_manager = &mut subscriptions.borrow_mut().resources;
             let resource_subscriptions = subscription_manager.get_mut(&resource_id).unwrap();
             resource_subscriptions.remove(&scope_id);
             if resource_subscriptions.is_empty() {
                 subscription_manager.remove(&resource_id);
             }
         });
+    }
 
-        world.resource()
+    /// Returns the `E` events sent since the last render of this component, the component is
+    /// re-rendered when new `E` events are sent.
+    pub fn use_event_reader<E: Event + Clone>() -> Vec<E> {
+        let scope_id = current_scope_id().unwrap();
+        let (cursor, subscriptions) = use_hook(|| {
+            let cursor = Rc::new(RefCell::new(EcsContext::<TT>::with_world(
+                get_cursor_current::<E>,
+            )));
+
+            let subscriptions = EcsContext::<TT>::get_subscriptions();
+            subscriptions
+                .borrow_mut()
+                .events
+                .entry(TypeId::of::<E>())
+                .or_insert_with(|| {
+                    let new_events_cursor =
+                        RefCell::new(EcsContext::<TT>::with_world(get_cursor_current::<E>));
+                    let new_events_exist = move |world: &World| {
+                        let Some(events) = world.get_resource::<Events<E>>() else {
+                            return false;
+                        };
+                        let mut new_events_cursor = new_events_cursor.borrow_mut();
+                        let new_events_exist = !new_events_cursor.is_empty(events);
+                        new_events_cursor.clear(events);
+                        new_events_exist
+                    };
+                    (Box::new(new_events_exist), HashSet::default())
+                })
+                .1
+                .insert(scope_id);
+            (cursor, subscriptions)
+        });
+        use_drop(move || {
+            let subscription_manager = &mut subscriptions.borrow_mut().events;
+            let (_, event_subscriptions) =
+                subscription_manager.get_mut(&TypeId::of::<E>()).unwrap();
+            event_subscriptions.remove(&scope_id);
+            if event_subscriptions.is_empty() {
+                subscription_manager.remove(&TypeId::of::<E>());
+            }
+        });
+
+        let mut cursor = cursor.borrow_mut();
+        EcsContext::<TT>::with_world(|world| {
+            world
+                .get_resource::<Events<E>>()
+                .map(|events| cursor.read(events).cloned().collect())
+                .unwrap_or_default()
+        })
     }
 }
 
+/// Cursor that only reads events sent after it was created.
+fn get_cursor_current<E: Event>(world: &World) -> EventCursor<E> {
+    world
+        .get_resource::<Events<E>>()
+        .map(Events::get_cursor_current)
+        .unwrap_or_default()
+}
+
 impl<TT: SptsDioxusTemplateNode> SptsDioxusHooks<TT> {
-    pub fn use_query<'a, Q>() -> UseQuery<'a, Q, ()>
+    pub fn use_query<Q>() -> UseQuery<Q, ()>
     where
         Q: ReadOnlyQueryData,
     {
         Self::use_query_filtered()
     }
 
-    pub fn use_query_filtered<'a, Q, F>() -> UseQuery<'a, Q, F>
+    pub fn use_query_filtered<Q, F>() -> UseQuery<Q, F>
     where
         Q: ReadOnlyQueryData,
         F: QueryFilter,
     {
-        let world = EcsContext::<TT>::get_world();
+        Self::use_change_subscription(query_result_changed::<Q, F>);
+        let system_state_slot = use_hook(|| {
+            let system_state = EcsContext::<TT>::with_world_mut(SystemState::new);
+            Rc::new(RefCell::new(Some(system_state)))
+        });
 
+        // The system state is kept across renders so `Changed` / `Added` filters are relative to
+        // the last render.  It's missing if the last `UseQuery` is still alive.
+        let system_state = system_state_slot
+            .borrow_mut()
+            .take()
+            .unwrap_or_else(|| EcsContext::<TT>::with_world_mut(SystemState::new));
+        UseQuery {
+            system_state: Some(system_state),
+            system_state_slot,
+            world: EcsContext::<TT>::get_world_scope(),
+        }
+    }
+}
+
+impl<TT: SptsDioxusTemplateNode> SptsDioxusHooks<TT> {
+    /// Re-renders the current scope when the fn returned by `create_changed` (called once, when
+    /// the hook is created) returns true.  It's called once per frame before rendering.
+    pub(crate) fn use_change_subscription<C>(create_changed: impl FnOnce(&mut World) -> C)
+    where
+        C: FnMut(&World) -> bool + 'static,
+    {
         let scope_id = current_scope_id().unwrap();
-        let subscription_manager = use_hook(|| {
-            let subscription_manager = &mut world
-                .non_send_resource_mut::<SptsDioxusContext<TT>>()
-                .subscriptions
-                .world_and_queries;
-            subscription_manager.insert(scope_id);
-            Box::as_mut(subscription_manager) as *mut HashSet<ScopeId>
+        let (change_id, subscriptions) = use_hook(|| {
+            let changed = EcsContext::<TT>::with_world_mut(create_changed);
+
+            let subscriptions = EcsContext::<TT>::get_subscriptions();
+            let change_id = {
+                let mut subscriptions = subscriptions.borrow_mut();
+                let change_id = subscriptions.next_change_id;
+                subscriptions.next_change_id += 1;
+                subscriptions
+                    .changes
+                    .insert(change_id, (scope_id, Box::new(changed)));
+                change_id
+            };
+            (change_id, subscriptions)
         });
         use_drop(move || {
-            unsafe { &mut *subscription_manager }.remove(&scope_id);
+            subscriptions.borrow_mut().changes.remove(&change_id);
         });
+    }
+}
 
-        UseQuery {
-            system_state: SystemState::new(world),
-            world_ref: world,
+/// Creates a fn that checks if the result of the `Q`, `F` query changed since it was last
+/// called, that is entities started / stopped matching, changed archetype (i.e. an `Option<&T>`
+/// component was added / removed) or had one of the components the query reads changed.
+fn query_result_changed<Q, F>(world: &mut World) -> impl FnMut(&World) -> bool
+where
+    Q: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+{
+    let mut query_state = QueryState::<(Entity, Q), F>::new(world);
+    let (component_ids, reads_all_components) = {
+        let (component_ids, reads_all_components) = query_state
+            .component_access()
+            .access()
+            .component_reads_and_writes();
+        (component_ids.collect::<Vec<_>>(), reads_all_components)
+    };
+
+    let matched_entities = |query_state: &mut QueryState<(Entity, Q), F>, world: &World| {
+        query_state.update_archetypes(world);
+        query_state
+            .iter_manual(world)
+            .map(|(entity, _)| (entity, world.entity(entity).archetype().id()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut last_run = world.read_change_tick();
+    let mut last_matched = matched_entities(&mut query_state, world);
+
+    move |world| {
+        // Can't track i.e. `EntityRef`, always re-render.
+        if reads_all_components {
+            return true;
         }
+
+        let this_run = world.read_change_tick();
+        let matched = matched_entities(&mut query_state, world);
+        let changed = matched != last_matched
+            || matched.iter().any(|(entity, _)| {
+                let entity_ref = world.entity(*entity);
+                component_ids.iter().any(|component_id| {
+                    entity_ref
+                        .get_change_ticks_by_id(*component_id)
+                        .is_some_and(|ticks| ticks.is_changed(last_run, this_run))
+                })
+            });
+
+        last_run = this_run;
+        last_matched = matched;
+        changed
     }
 }
 
-pub struct UseQuery<'a, Q: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> {
-    system_state: SystemState<Query<'static, 'static, Q, F>>,
-    world_ref: &'a World,
+type QuerySystemState<Q, F> = SystemState<Query<'static, 'static, Q, F>>;
+
+pub struct UseQuery<Q: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> {
+    system_state: Option<QuerySystemState<Q, F>>,
+    /// Hook storage that the system state is returned to when this is dropped.
+    system_state_slot: Rc<RefCell<Option<QuerySystemState<Q, F>>>>,
+    world: WorldScope,
 }
 
-impl<'a, Q, F> UseQuery<'a, Q, F>
+impl<Q: ReadOnlyQueryData + 'static, F: QueryFilter + 'static> Drop for UseQuery<Q, F> {
+    fn drop(&mut self) {
+        *self.system_state_slot.borrow_mut() = self.system_state.take();
+    }
+}
+
+impl<Q, F> UseQuery<Q, F>
 where
     Q: ReadOnlyQueryData,
     F: QueryFilter,
 {
-    pub fn query(&mut self) -> Query<Q, F> {
-        self.system_state.get(self.world_ref)
+    /// Calls `f` with the query, panics if the root isn't rendering or handling events.
+    pub fn query<R>(&mut self, f: impl FnOnce(Query<'_, '_, Q, F>) -> R) -> R {
+        let system_state = self.system_state.as_mut().unwrap();
+        self.world.with_world(|world| f(system_state.get(world)))
     }
 }
 
@@ -153,19 +421,14 @@ impl<TT: SptsDioxusTemplateNode> SptsDioxusHooks<TT> {
     pub fn use_world_memo<TResult: PartialEq>(
         mut memo_fn: impl FnMut(&mut World) -> TResult + 'static,
     ) -> Memo<TResult> {
-        let value = use_memo(move || {
-            let world = EcsContext::<TT>::get_world();
-            memo_fn(world)
-        });
-        value
+        use_memo(move || EcsContext::<TT>::with_world_mut(&mut memo_fn))
     }
 
     pub fn use_world_callback<TArgs: 'static, TResult: 'static>(
         mut callback_fn: impl FnMut(&mut World, TArgs) -> TResult + 'static,
     ) -> Callback<TArgs, TResult> {
         use_callback(move |args| {
-            let world = EcsContext::<TT>::get_world();
-            callback_fn(world, args)
+            EcsContext::<TT>::with_world_mut(|world| callback_fn(world, args))
         })
     }
 }
@@ -0,0 +1,140 @@
+use std::fmt::Display;
+
+use bevy_ecs::{entity::Entity, event::Event, system::Resource};
+use dioxus::dioxus_core::ElementId;
+
+/// What went wrong while applying a mutation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SptsDioxusErrorKind {
+    /// No entity is mapped to the ElementId.
+    UnknownElementId(ElementId),
+    /// The entity was despawned outside of dioxus.
+    EntityDespawned(Entity),
+    /// The entity was moved outside of dioxus, to a parent that dioxus doesn't manage (or out of
+    /// the hierarchy if `parent` is `None`).
+    Reparented {
+        entity: Entity,
+        parent: Option<Entity>,
+    },
+    /// The entity is missing a component that dioxus relies on, i.e. `Parent`.
+    MissingComponent {
+        entity: Entity,
+        component: &'static str,
+    },
+    /// The entity doesn't have a child at `index`, usually because the hierarchy was changed
+    /// outside of dioxus.
+    MissingChild { entity: Entity, index: usize },
+    /// `child` isn't one of `parent`'s children.
+    NotAChild { parent: Entity, child: Entity },
+    /// Dioxus expected more nodes on the stack than there are.
+    StackUnderflow { expected: usize, found: usize },
+    /// The template doesn't have a root node at `index`.
+    MissingTemplateRoot { index: usize, roots: usize },
+    /// A dioxus element that the adapter doesn't define.
+    UnknownElement {
+        tag: &'static str,
+        namespace: Option<&'static str>,
+    },
+    /// An attribute that the element doesn't define.
+    UnknownAttribute {
+        tag: &'static str,
+        name: &'static str,
+    },
+    /// The attribute value isn't the type that the attribute expects.
+    InvalidAttributeValue {
+        name: &'static str,
+        expected: &'static str,
+    },
+}
+
+impl Display for SptsDioxusErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownElementId(id) => write!(f, "No entity for element {id:?}."),
+            Self::EntityDespawned(entity) => {
+                write!(f, "Entity {entity} was despawned outside of dioxus.")
+            }
+            Self::Reparented { entity, parent } => write!(
+                f,
+                "Entity {entity} was moved to parent {parent:?} outside of dioxus."
+            ),
+            Self::MissingComponent { entity, component } => {
+                write!(f, "Entity {entity} is missing component '{component}'.")
+            }
+            Self::MissingChild { entity, index } => {
+                write!(f, "Entity {entity} has no child at index {index}.")
+            }
+            Self::NotAChild { parent, child } => {
+                write!(f, "Entity {child} isn't a child of {parent}.")
+            }
+            Self::StackUnderflow { expected, found } => write!(
+                f,
+                "Expected {expected} nodes on the stack but found {found}."
+            ),
+            Self::MissingTemplateRoot { index, roots } => write!(
+                f,
+                "Template has {roots} root nodes, there's no root at index {index}."
+            ),
+            Self::UnknownElement { tag, namespace } => {
+                write!(f, "Unknown element '{tag}' with namespace {namespace:?}.")
+            }
+            Self::UnknownAttribute { tag, name } => {
+                write!(f, "Unexpected attribute '{name}' on element '{tag}'.")
+            }
+            Self::InvalidAttributeValue { name, expected } => write!(
+                f,
+                "Value passed to attribute '{name}' wasn't a '{expected}'."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SptsDioxusErrorKind {}
+
+/// Sent when a mutation from dioxus couldn't be applied to the world.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct SptsDioxusError {
+    /// Entity with the `SptsDioxusRootComponent` that the mutation belongs to.
+    pub root: Entity,
+    pub element: Option<ElementId>,
+    /// Name of the `WriteMutations` method, i.e. `set_attribute`, or `managed` when a
+    /// `SptsDioxusManaged` entity was changed outside of dioxus.
+    pub mutation: &'static str,
+    pub kind: SptsDioxusErrorKind,
+}
+
+impl Display for SptsDioxusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} on {:?} of root {}: {}",
+            self.mutation, self.element, self.root, self.kind
+        )
+    }
+}
+
+impl std::error::Error for SptsDioxusError {}
+
+/// Decides what happens when a mutation can't be applied.  The failed mutation is skipped unless
+/// the policy is [SptsDioxusErrorPolicy::Panic].
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SptsDioxusErrorPolicy {
+    /// Panic with the error.
+    Panic,
+    /// Log the error and send it as a [SptsDioxusError] event.
+    #[default]
+    Log,
+    /// Only send the error as a [SptsDioxusError] event.
+    Skip,
+}
+
+impl SptsDioxusErrorPolicy {
+    /// Panics or logs `error`, sending the event is up to the caller.
+    pub(crate) fn report(&self, error: &SptsDioxusError) {
+        match self {
+            Self::Panic => panic!("bevy_spts_dioxus: {error}"),
+            Self::Log => bevy_utils::tracing::error!("bevy_spts_dioxus: {error}"),
+            Self::Skip => {}
+        }
+    }
+}
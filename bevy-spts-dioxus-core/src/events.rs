@@ -0,0 +1,297 @@
+use std::{
+    any::{Any, TypeId},
+    rc::Rc,
+    sync::Arc,
+};
+
+use bevy_app::App;
+use bevy_derive::Deref;
+use bevy_ecs::{
+    bundle::Bundle,
+    component::Component,
+    entity::Entity,
+    event::{Event, EventCursor, EventReader, EventWriter, Events},
+    observer::{Observer, Trigger},
+    schedule::IntoSystemConfigs,
+    system::{IntoObserverSystem, Resource},
+    world::{EntityWorldMut, World},
+};
+use bevy_utils::HashSet;
+use dioxus::{
+    dioxus_core::{AttributeValue, Event as DioxusEvent},
+    prelude::{with_owner, Attribute, EventHandler, IntoAttributeValue, SuperInto},
+    signals::{AnyStorage, UnsyncStorage},
+};
+
+use crate::{SptsDioxusEventSources, SptsDioxusSchedule};
+
+/// Records the dioxus event listeners (i.e. `click` for `onclick`) that are attached to an entity.
+///
+/// Kept in sync by the `MutationApplier`, systems that produce [SptsDioxusEvent]s can use this
+/// to skip entities that nobody is listening to.
+#[derive(Component, Deref, Debug, Default, Clone)]
+pub struct SptsDioxusEventListeners(HashSet<&'static str>);
+
+impl SptsDioxusEventListeners {
+    pub fn has_listener(&self, name: &str) -> bool {
+        self.0.contains(name)
+    }
+}
+
+pub(crate) fn insert_event_listener(name: &'static str, mut entity_mut: EntityWorldMut) {
+    if let Some(mut listeners) = entity_mut.get_mut::<SptsDioxusEventListeners>() {
+        listeners.0.insert(name);
+    } else {
+        entity_mut.insert(SptsDioxusEventListeners(HashSet::from_iter([name])));
+    }
+}
+
+pub(crate) fn remove_event_listener(name: &'static str, mut entity_mut: EntityWorldMut) {
+    let Some(mut listeners) = entity_mut.get_mut::<SptsDioxusEventListeners>() else {
+        return;
+    };
+    listeners.0.remove(name);
+    if listeners.0.is_empty() {
+        entity_mut.remove::<SptsDioxusEventListeners>();
+    }
+}
+
+/// A bevy side event that will be dispatched into the dioxus `VirtualDom` on the next tick.
+///
+/// If `target` isn't managed by dioxus (i.e. it was spawned as a child by a bevy system), the
+/// event will be dispatched to the closest dioxus managed ancestor.  Bubbling events are dispatched
+/// to the closest one that listens to the event.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Clone)]
+/// pub struct PressData { pub strength: f32 }
+///
+/// fn send_press(mut writer: EventWriter<SptsDioxusEvent>, pressed: Query<Entity, With<Pressed>>) {
+///     for entity in &pressed {
+///         // Handled by `onpress: move |e: DioxusEvent<PressData>| { ... }`
+///         writer.send(SptsDioxusEvent::new(entity, "press", PressData { strength: 1. }, true));
+///     }
+/// }
+/// ```
+#[derive(Event, Clone)]
+pub struct SptsDioxusEvent {
+    pub target: Entity,
+    /// Name of the event without the `on` prefix, i.e. `click` for `onclick`.
+    pub name: &'static str,
+    pub bubbles: bool,
+    data: Arc<dyn Fn() -> Rc<dyn Any> + Send + Sync>,
+}
+
+impl SptsDioxusEvent {
+    pub fn new<T: Clone + Send + Sync + 'static>(
+        target: Entity,
+        name: &'static str,
+        data: T,
+        bubbles: bool,
+    ) -> Self {
+        Self {
+            target,
+            name,
+            bubbles,
+            data: Arc::new(move || Rc::new(data.clone())),
+        }
+    }
+
+    /// Creates the event payload passed to the dioxus event handler.
+    pub fn data(&self) -> Rc<dyn Any> {
+        (self.data)()
+    }
+}
+
+impl std::fmt::Debug for SptsDioxusEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SptsDioxusEvent")
+            .field("target", &self.target)
+            .field("name", &self.name)
+            .field("bubbles", &self.bubbles)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct EventReaders {
+    events: EventCursor<SptsDioxusEvent>,
+}
+
+impl EventReaders {
+    pub fn read_events(&mut self, events: &Events<SptsDioxusEvent>) -> Vec<SptsDioxusEvent> {
+        self.events.read(events).cloned().collect()
+    }
+}
+
+/// Implement on a bevy [Event] to use it in an `#[event]` field of a `#[define_element]` struct.
+///
+/// Events sent with an `EventWriter` are dispatched to [SptsDioxusElementEvent::target], events
+/// triggered with `commands.trigger_targets(event, entity)` are dispatched to `entity`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Event, Clone)]
+/// pub struct PressEvent { pub entity: Entity }
+///
+/// impl SptsDioxusElementEvent for PressEvent {
+///     fn target(&self) -> Option<Entity> {
+///         Some(self.entity)
+///     }
+/// }
+/// ```
+pub trait SptsDioxusElementEvent: Event + Clone {
+    /// Entity that this event targets when it's sent with an `EventWriter`.
+    fn target(&self) -> Option<Entity> {
+        None
+    }
+
+    /// Whether the dioxus event should bubble up to the listeners of the target's ancestors.
+    fn bubbles(&self) -> bool {
+        true
+    }
+
+    /// Whether triggered events are forwarded, disable it for events that are both triggered and
+    /// sent with an `EventWriter` so they're only forwarded once.
+    const FORWARD_TRIGGERS: bool = true;
+}
+
+/// The `(event type, name)` pairs that [register_element_event] forwards, so each one is only
+/// forwarded once.
+#[derive(Resource, Default)]
+pub(crate) struct RegisteredElementEvents(HashSet<(TypeId, &'static str)>);
+
+/// Forwards `E` events (sent via `EventWriter` or triggered as observers) to the dioxus listeners
+/// named `name`.  Called by the code generated for `#[event]` fields, after the
+/// `SptsDioxusPlugin` is added.  Registering the same event and name again does nothing.
+pub fn register_element_event<E: SptsDioxusElementEvent>(app: &mut App, name: &'static str) {
+    let SptsDioxusSchedule(schedule) = *app
+        .world()
+        .get_resource::<SptsDioxusSchedule>()
+        .expect("bevy_spts_dioxus: Element events are registered by the SptsDioxusPlugin.");
+    let newly_registered = app
+        .world_mut()
+        .resource_mut::<RegisteredElementEvents>()
+        .0
+        .insert((TypeId::of::<E>(), name));
+    if !newly_registered {
+        return;
+    }
+    app.add_event::<E>().add_systems(
+        schedule,
+        forward_element_events::<E>(name).in_set(SptsDioxusEventSources),
+    );
+    if E::FORWARD_TRIGGERS {
+        app.add_observer(forward_triggered_element_event::<E>(name));
+    }
+}
+
+fn forward_element_events<E: SptsDioxusElementEvent>(
+    name: &'static str,
+) -> impl FnMut(EventReader<E>, EventWriter<SptsDioxusEvent>) {
+    move |mut reader, mut writer| {
+        for event in reader.read() {
+            if let Some(target) = event.target() {
+                writer.send(SptsDioxusEvent::new(target, name, event.clone(), event.bubbles()));
+            }
+        }
+    }
+}
+
+fn forward_triggered_element_event<E: SptsDioxusElementEvent>(
+    name: &'static str,
+) -> impl FnMut(Trigger<E>, EventWriter<SptsDioxusEvent>) {
+    move |trigger, mut writer| {
+        let event = trigger.event();
+        let target = if trigger.entity() == Entity::PLACEHOLDER {
+            event.target()
+        } else {
+            Some(trigger.entity())
+        };
+        if let Some(target) = target {
+            writer.send(SptsDioxusEvent::new(target, name, event.clone(), event.bubbles()));
+        }
+    }
+}
+
+/// Creates the listener attribute for an `#[event]` field.  Called by the
+/// `dioxus_elements::events` functions generated by `#[bevy_spts_dioxus]`.
+pub fn element_event_listener<T: 'static, M>(
+    name: &'static str,
+    handler: impl SuperInto<EventHandler<DioxusEvent<T>>, M>,
+) -> Attribute {
+    // Run super_into with an owner that is moved into the listener so the handler is dropped with
+    // the listener, same as dioxus-html's events.
+    let owner = <UnsyncStorage as AnyStorage>::owner();
+    let event_handler = with_owner(owner.clone(), || handler.super_into());
+    Attribute::new(
+        name,
+        AttributeValue::listener(move |event: DioxusEvent<T>| {
+            _ = &owner;
+            event_handler.call(event);
+        }),
+        None,
+        false,
+    )
+}
+
+/// Bevy observer attached to an element's entity with the built-in `observe` attribute.  It's
+/// despawned when the element is unmounted or the attribute is set to another observer.
+///
+/// # Example
+///
+/// ```ignore
+/// let on_hit = use_hook(|| {
+///     SptsDioxusObserver::new(|trigger: Trigger<Hit>, mut commands: Commands| {
+///         commands.entity(trigger.entity()).insert(Flash);
+///     })
+/// });
+/// rsx! {
+///     spatial {
+///         observe: on_hit,
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SptsDioxusObserver {
+    spawn: Rc<SpawnObserver>,
+}
+
+/// Spawns the observer watching the element's entity, returns the observer's entity.
+type SpawnObserver = dyn Fn(&mut World, Entity) -> Entity;
+
+impl SptsDioxusObserver {
+    pub fn new<E: Event, B: Bundle, M>(
+        system: impl IntoObserverSystem<E, B, M> + Clone + 'static,
+    ) -> Self {
+        Self {
+            spawn: Rc::new(move |world, entity| {
+                world
+                    .spawn(Observer::new(system.clone()).with_entity(entity))
+                    .id()
+            }),
+        }
+    }
+
+    /// Spawns the observer watching `entity`, returns the observer's entity.
+    pub(crate) fn spawn(&self, world: &mut World, entity: Entity) -> Entity {
+        (self.spawn)(world, entity)
+    }
+}
+
+/// Observers are compared by identity, create them in a hook (i.e. `use_hook`) so the observer
+/// isn't replaced on every render.
+impl PartialEq for SptsDioxusObserver {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.spawn, &other.spawn)
+    }
+}
+
+impl IntoAttributeValue for SptsDioxusObserver {
+    fn into_value(self) -> AttributeValue {
+        AttributeValue::any_value(self)
+    }
+}
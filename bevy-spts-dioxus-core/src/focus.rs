@@ -0,0 +1,240 @@
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    event::EventWriter,
+    query::With,
+    system::{Query, Res, ResMut, Resource},
+};
+use bevy_hierarchy::Children;
+use bevy_input::{
+    gamepad::{Gamepad, GamepadButton},
+    keyboard::KeyCode,
+    ButtonInput,
+};
+
+use crate::{events::SptsDioxusEvent, SptsDioxusRootComponent};
+
+/// Makes an entity focusable, inserted by the built-in `tabindex` attribute.
+///
+/// Same as html, entities with a positive index come first in the tab order (lowest first), then
+/// the ones with `0` in the order that they're spawned in the hierarchy.  Entities with a negative
+/// index can only be focused with [SptsDioxusFocus::focus].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SptsDioxusTabIndex(pub i32);
+
+/// Tracks the focused dioxus managed entity.
+///
+/// `Tab` / `Shift+Tab` and the gamepad dpad move focus through the [SptsDioxusTabIndex] entities,
+/// `onfocus` / `onblur` are sent to the entities that gain / lose focus and `onkeydown` is sent
+/// to the focused entity for each key or gamepad button that's pressed.
+#[derive(Resource, Debug, Default)]
+pub struct SptsDioxusFocus {
+    focused: Option<Entity>,
+    /// Focus move requested by [SptsDioxusFocus::focus_next] / [SptsDioxusFocus::focus_prev].
+    requested_move: Option<FocusMove>,
+    /// Entity that `onfocus` was last sent to, used to send `onblur` when focus moves.
+    notified: Option<Entity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FocusMove {
+    Next,
+    Prev,
+}
+
+impl SptsDioxusFocus {
+    pub fn focused(&self) -> Option<Entity> {
+        self.focused
+    }
+
+    pub fn is_focused(&self, entity: Entity) -> bool {
+        self.focused == Some(entity)
+    }
+
+    /// Focuses `entity`, it's ignored unless the entity has a [SptsDioxusTabIndex].
+    pub fn focus(&mut self, entity: Entity) {
+        self.focused = Some(entity);
+        self.requested_move = None;
+    }
+
+    pub fn blur(&mut self) {
+        self.focused = None;
+        self.requested_move = None;
+    }
+
+    /// Moves focus to the next entity in the tab order when the focus is next updated.
+    pub fn focus_next(&mut self) {
+        self.requested_move = Some(FocusMove::Next);
+    }
+
+    /// Moves focus to the previous entity in the tab order when the focus is next updated.
+    pub fn focus_prev(&mut self) {
+        self.requested_move = Some(FocusMove::Prev);
+    }
+}
+
+/// Payload of the built-in `onfocus` and `onblur` events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SptsDioxusFocusData {
+    /// Entity that gained / lost focus.
+    pub target: Entity,
+}
+
+/// Key or gamepad button that was pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SptsDioxusKey {
+    Keyboard(KeyCode),
+    Gamepad {
+        gamepad: Entity,
+        button: GamepadButton,
+    },
+}
+
+/// Payload of the built-in `onkeydown` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SptsDioxusKeyData {
+    /// Focused entity that the event was sent to.
+    pub target: Entity,
+    pub key: SptsDioxusKey,
+}
+
+/// Entities with a non negative [SptsDioxusTabIndex] in tab order.
+fn tab_order(
+    roots: &Query<Entity, With<SptsDioxusRootComponent>>,
+    children: &Query<&Children>,
+    tab_indices: &Query<&SptsDioxusTabIndex>,
+) -> Vec<Entity> {
+    let mut order = Vec::new();
+    // Depth first so entities are in hierarchy order.
+    let mut stack: Vec<Entity> = roots.iter().collect();
+    stack.reverse();
+    while let Some(entity) = stack.pop() {
+        if let Ok(tab_index) = tab_indices.get(entity) {
+            if tab_index.0 >= 0 {
+                order.push((tab_index.0, entity));
+            }
+        }
+        if let Ok(entity_children) = children.get(entity) {
+            stack.extend(entity_children.iter().rev());
+        }
+    }
+    // Stable so entities with the same index keep their hierarchy order.
+    order.sort_by_key(|(tab_index, _)| {
+        if *tab_index == 0 {
+            i32::MAX
+        } else {
+            *tab_index
+        }
+    });
+    order.into_iter().map(|(_, entity)| entity).collect()
+}
+
+fn move_focus(focused: Option<Entity>, order: &[Entity], focus_move: FocusMove) -> Option<Entity> {
+    if order.is_empty() {
+        return None;
+    }
+    let position = focused.and_then(|focused| order.iter().position(|e| *e == focused));
+    let index = match (position, focus_move) {
+        (None, FocusMove::Next) => 0,
+        (None, FocusMove::Prev) => order.len() - 1,
+        (Some(position), FocusMove::Next) => (position + 1) % order.len(),
+        (Some(position), FocusMove::Prev) => (position + order.len() - 1) % order.len(),
+    };
+    Some(order[index])
+}
+
+/// Sends `onkeydown` for the pressed keys, moves focus and sends `onfocus` / `onblur` when the
+/// focused entity changed.
+pub(crate) fn update_focus(
+    mut focus: ResMut<SptsDioxusFocus>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    roots: Query<Entity, With<SptsDioxusRootComponent>>,
+    children: Query<&Children>,
+    tab_indices: Query<&SptsDioxusTabIndex>,
+    mut writer: EventWriter<SptsDioxusEvent>,
+) {
+    // Focus is lost when the entity is despawned or isn't focusable.
+    if focus
+        .focused
+        .is_some_and(|focused| !tab_indices.contains(focused))
+    {
+        focus.focused = None;
+    }
+
+    let mut focus_move = focus.requested_move;
+    let mut pressed = Vec::new();
+    if let Some(keys) = keys {
+        let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        for key in keys.get_just_pressed() {
+            pressed.push(SptsDioxusKey::Keyboard(*key));
+            if *key == KeyCode::Tab {
+                focus_move = Some(if shift {
+                    FocusMove::Prev
+                } else {
+                    FocusMove::Next
+                });
+            }
+        }
+    }
+    for (gamepad_entity, gamepad) in &gamepads {
+        for button in gamepad.get_just_pressed() {
+            pressed.push(SptsDioxusKey::Gamepad {
+                gamepad: gamepad_entity,
+                button: *button,
+            });
+            match button {
+                GamepadButton::DPadDown | GamepadButton::DPadRight => {
+                    focus_move = Some(FocusMove::Next)
+                }
+                GamepadButton::DPadUp | GamepadButton::DPadLeft => {
+                    focus_move = Some(FocusMove::Prev)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Key events go to the entity that was focused when the key was pressed.
+    if let Some(focused) = focus.focused {
+        for key in pressed {
+            writer.send(SptsDioxusEvent::new(
+                focused,
+                "keydown",
+                SptsDioxusKeyData {
+                    target: focused,
+                    key,
+                },
+                true,
+            ));
+        }
+    }
+
+    if let Some(focus_move) = focus_move {
+        let order = tab_order(&roots, &children, &tab_indices);
+        let focused = move_focus(focus.focused, &order, focus_move);
+        focus.focused = focused;
+        focus.requested_move = None;
+    }
+
+    if focus.focused == focus.notified {
+        return;
+    }
+    if let Some(blurred) = focus.notified {
+        writer.send(SptsDioxusEvent::new(
+            blurred,
+            "blur",
+            SptsDioxusFocusData { target: blurred },
+            false,
+        ));
+    }
+    if let Some(focused) = focus.focused {
+        writer.send(SptsDioxusEvent::new(
+            focused,
+            "focus",
+            SptsDioxusFocusData { target: focused },
+            false,
+        ));
+    }
+    focus.notified = focus.focused;
+}
@@ -1 +1,4 @@
+pub mod use_commands;
+pub mod use_component;
 pub mod use_entity;
+pub mod use_focus;
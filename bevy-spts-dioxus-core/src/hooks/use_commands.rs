@@ -0,0 +1,122 @@
+use std::{cell::RefCell, marker::PhantomData, mem, rc::Rc};
+
+use bevy_ecs::{
+    system::{Commands, Resource},
+    world::{Command, World},
+};
+use dioxus::{dioxus_core::use_hook, prelude::consume_context};
+
+use crate::{adapter::SptsDioxusTemplateNode, prelude::SptsDioxusHooks};
+
+/// Writes to the world queued by hooks.  Applied by `tick_dioxus_ui` once the root has rendered,
+/// when nothing else is borrowing the world.
+#[derive(Clone, Default)]
+#[allow(clippy::type_complexity)]
+pub(crate) struct DeferredWorldWrites(Rc<RefCell<Vec<Box<dyn FnOnce(&mut World)>>>>);
+
+impl DeferredWorldWrites {
+    fn push(&self, write: impl FnOnce(&mut World) + 'static) {
+        self.0.borrow_mut().push(Box::new(write));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    pub fn apply(&self, world: &mut World) {
+        // Taken first so writes that queue more writes don't hold the borrow.
+        let writes = mem::take(&mut *self.0.borrow_mut());
+        for write in writes {
+            write(world);
+        }
+    }
+}
+
+/// Handle returned by [SptsDioxusHooks::use_commands].
+#[derive(Clone)]
+pub struct UseCommands {
+    writes: DeferredWorldWrites,
+}
+
+impl UseCommands {
+    /// Runs `f` with `Commands` after the render pass, the commands are applied straight after.
+    pub fn with(&self, f: impl FnOnce(&mut Commands) + 'static) {
+        self.writes.push(move |world| {
+            f(&mut world.commands());
+            world.flush();
+        });
+    }
+
+    /// Applies `command` after the render pass.
+    pub fn queue(&self, command: impl Command) {
+        self.writes.push(move |world| command.apply(world));
+    }
+}
+
+/// Handle returned by [SptsDioxusHooks::use_resource_mut].
+pub struct UseResourceMut<R: Resource> {
+    writes: DeferredWorldWrites,
+    pd: PhantomData<R>,
+}
+
+impl<R: Resource> Clone for UseResourceMut<R> {
+    fn clone(&self) -> Self {
+        Self {
+            writes: self.writes.clone(),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl<R: Resource> UseResourceMut<R> {
+    /// Mutates the resource after the render pass.
+    pub fn write(&self, f: impl FnOnce(&mut R) + 'static) {
+        self.writes.push(move |world| match world.get_resource_mut::<R>() {
+            Some(mut resource) => f(&mut resource),
+            None => bevy_utils::tracing::warn!(
+                "bevy_spts_dioxus: Couldn't write to resource '{}' as it doesn't exist.",
+                std::any::type_name::<R>()
+            ),
+        });
+    }
+}
+
+impl<TT: SptsDioxusTemplateNode> SptsDioxusHooks<TT> {
+    /// Returns a handle that queues `Commands` to be applied after the render pass.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let commands = Hooks::use_commands();
+    /// rsx! {
+    ///     spatial {
+    ///         onpress: move |_| commands.with(|commands| { commands.spawn(Explosion); }),
+    ///     }
+    /// }
+    /// ```
+    pub fn use_commands() -> UseCommands {
+        use_hook(|| UseCommands {
+            writes: consume_context::<DeferredWorldWrites>(),
+        })
+    }
+
+    /// Returns a handle that mutates `R` after the render pass, scopes using
+    /// `use_bevy_resource::<R>()` are re-rendered on the next frame.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let score = Hooks::use_resource_mut::<Score>();
+    /// rsx! {
+    ///     spatial {
+    ///         onpress: move |_| score.write(|score| score.0 += 1),
+    ///     }
+    /// }
+    /// ```
+    pub fn use_resource_mut<R: Resource>() -> UseResourceMut<R> {
+        use_hook(|| UseResourceMut {
+            writes: consume_context::<DeferredWorldWrites>(),
+            pd: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use std::{cell::Cell, rc::Rc};
+
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use dioxus::dioxus_core::use_hook;
+
+use crate::{adapter::SptsDioxusTemplateNode, ecs_hooks::EcsContext, prelude::SptsDioxusHooks};
+
+impl<TT: SptsDioxusTemplateNode> SptsDioxusHooks<TT> {
+    /// Returns a clone of `entity`'s `C` component, the scope is re-rendered when `C` is added,
+    /// changed or removed on the entity.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[component]
+    /// pub fn MyComponent() -> Element {
+    ///     let my_entity = Hooks::use_entity();
+    ///     // `None` until the element is spawned, reading the signal re-renders once it is.
+    ///     let transform = Hooks::use_component::<Transform>(my_entity);
+    ///     rsx! {
+    ///         spatial {
+    ///             entity: my_entity,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn use_component<C: Component + Clone>(entity: impl Into<Option<Entity>>) -> Option<C> {
+        let entity = entity.into();
+
+        // The entity and whether it had `C` when last rendered / checked.
+        let watched = use_hook(|| Rc::new(Cell::new((None::<Entity>, false))));
+        Self::use_change_subscription({
+            let watched = watched.clone();
+            move |world: &mut World| {
+                let component_id = world.register_component::<C>();
+                let mut last_run = world.read_change_tick();
+                move |world: &World| {
+                    let this_run = world.read_change_tick();
+                    let (entity, had_component) = watched.get();
+                    let ticks = entity
+                        .and_then(|entity| world.get_entity(entity).ok())
+                        .and_then(|entity_ref| entity_ref.get_change_ticks_by_id(component_id));
+                    let changed = ticks.is_some() != had_component
+                        || ticks.is_some_and(|ticks| ticks.is_changed(last_run, this_run));
+
+                    watched.set((entity, ticks.is_some()));
+                    last_run = this_run;
+                    changed
+                }
+            }
+        });
+
+        let component = EcsContext::<TT>::with_world(|world| {
+            entity.and_then(|entity| world.get::<C>(entity)).cloned()
+        });
+        watched.set((entity, component.is_some()));
+        component
+    }
+}
@@ -3,8 +3,8 @@ use bevy_ecs::entity::Entity;
 use dioxus::{
     dioxus_core::AttributeValue,
     hooks::use_signal,
-    prelude::{use_hook, IntoAttributeValue},
-    signals::Signal,
+    prelude::IntoAttributeValue,
+    signals::{Readable, Signal},
 };
 
 use crate::{adapter::SptsDioxusTemplateNode, prelude::SptsDioxusHooks};
@@ -18,13 +18,20 @@ impl IntoAttributeValue for EntitySignal {
     }
 }
 
+/// Reads the signal, i.e. for `Hooks::use_component`.
+impl From<EntitySignal> for Option<Entity> {
+    fn from(value: EntitySignal) -> Self {
+        *value.0.read()
+    }
+}
+
 impl<TT: SptsDioxusTemplateNode> SptsDioxusHooks<TT> {
     /// Returns an EntitySignal that can be passed via the `entity` attribute
     /// to an element to get a handle to the entity in the scene.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```ignore
     /// #[component]
     /// pub fn MyComponent() {
     ///     let my_entity = Hooks:use_entity();
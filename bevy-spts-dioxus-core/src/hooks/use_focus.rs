@@ -0,0 +1,71 @@
+use bevy_ecs::entity::Entity;
+
+use crate::{
+    adapter::SptsDioxusTemplateNode, ecs_hooks::EcsContext, focus::SptsDioxusFocus,
+    hooks::use_commands::UseResourceMut, prelude::SptsDioxusHooks,
+};
+
+/// Handle returned by [SptsDioxusHooks::use_focus].
+#[derive(Clone)]
+pub struct UseFocus {
+    focused: Option<Entity>,
+    focus: UseResourceMut<SptsDioxusFocus>,
+}
+
+impl UseFocus {
+    /// Entity that was focused when the component rendered.
+    pub fn focused(&self) -> Option<Entity> {
+        self.focused
+    }
+
+    pub fn is_focused(&self, entity: Entity) -> bool {
+        self.focused == Some(entity)
+    }
+
+    /// Focuses `entity` after the render pass, it needs a `tabindex` attribute.
+    pub fn focus(&self, entity: Entity) {
+        self.focus.write(move |focus| focus.focus(entity));
+    }
+
+    pub fn blur(&self) {
+        self.focus.write(SptsDioxusFocus::blur);
+    }
+
+    pub fn focus_next(&self) {
+        self.focus.write(SptsDioxusFocus::focus_next);
+    }
+
+    pub fn focus_prev(&self) {
+        self.focus.write(SptsDioxusFocus::focus_prev);
+    }
+}
+
+impl<TT: SptsDioxusTemplateNode> SptsDioxusHooks<TT> {
+    /// Returns a handle to read and move the focus, the component is re-rendered when the focus
+    /// changes.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let focus = Hooks::use_focus();
+    /// rsx! {
+    ///     spatial {
+    ///         tabindex: 0,
+    ///         onkeydown: move |e: DioxusEvent<SptsDioxusKeyData>| {
+    ///             if e.key == SptsDioxusKey::Keyboard(KeyCode::Escape) {
+    ///                 focus.blur();
+    ///             }
+    ///         },
+    ///     }
+    /// }
+    /// ```
+    pub fn use_focus() -> UseFocus {
+        Self::use_resource_subscription::<SptsDioxusFocus>();
+        let focused =
+            EcsContext::<TT>::with_world(|world| world.resource::<SptsDioxusFocus>().focused());
+        UseFocus {
+            focused,
+            focus: Self::use_resource_mut::<SptsDioxusFocus>(),
+        }
+    }
+}
@@ -1,94 +1,266 @@
 mod adapter;
 mod deferred_system;
 mod ecs_hooks;
+mod error;
+mod events;
+mod focus;
+mod managed;
 // mod elements;
+mod mutation_log;
 mod mutations;
+#[cfg(feature = "bevy_picking")]
+mod picking;
+mod root;
+mod snapshot;
+#[cfg(feature = "testing")]
+mod testing;
 mod tick;
 mod hooks;
 mod utils;
 
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, marker::PhantomData};
 
 use bevy_app::{App, Last, Plugin};
-use bevy_derive::Deref;
-use bevy_ecs::{entity::EntityHashMap, prelude::*};
+use bevy_ecs::{
+    entity::EntityHashMap,
+    prelude::*,
+    schedule::{InternedScheduleLabel, ScheduleLabel},
+};
 use bevy_utils::HashMap;
 use dioxus::{
-    dioxus_core::{ElementId, VirtualDom},
-    prelude::Element, signals::Signal,
+    dioxus_core::{AttributeValue, ElementId, Template, VirtualDom},
+    signals::Signal,
 };
 
 use adapter::SptsDioxusTemplateNode;
 use deferred_system::DeferredSystemRunQueue;
-use ecs_hooks::EcsSubscriptions;
+use ecs_hooks::{EcsContext, SharedEcsSubscriptions, WorldScope};
+use error::{SptsDioxusError, SptsDioxusErrorPolicy};
+use events::{EventReaders, RegisteredElementEvents, SptsDioxusEvent};
+use focus::{update_focus, SptsDioxusFocus};
+use managed::{check_managed_entities, SptsDioxusManagedPolicy};
+use hooks::use_commands::DeferredWorldWrites;
+use mutation_log::SptsDioxusMutationLog;
 use mutations::BevyTemplate;
-use tick::tick_dioxus_ui;
+use snapshot::SptsDioxusSnapshotComponents;
+use tick::{apply_mutations, render_roots, run_deferred_systems, update_subscriptions};
+
+pub use root::SptsDioxusRootComponent;
 
+/// Adds the dioxus roots (entities with a [SptsDioxusRootComponent]) to the app, rendering them in
+/// the [Last] schedule unless another schedule is passed with [SptsDioxusPlugin::in_schedule].
 #[derive(Debug, Clone, Copy)]
 pub struct SptsDioxusPlugin<TT: SptsDioxusTemplateNode> {
+    schedule: InternedScheduleLabel,
     template_pd: PhantomData<TT>,
 }
 
 impl<TT: SptsDioxusTemplateNode> Default for SptsDioxusPlugin<TT> {
     fn default() -> Self {
         Self {
+            schedule: Last.intern(),
             template_pd: PhantomData,
         }
     }
 }
 
+impl<TT: SptsDioxusTemplateNode> SptsDioxusPlugin<TT> {
+    /// Runs the [SptsDioxusSet]s in `schedule`, i.e. `PostUpdate` so the `Transform`s the UI
+    /// changes are propagated in the same frame:
+    ///
+    /// ```ignore
+    /// app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default().in_schedule(PostUpdate))
+    ///     .configure_sets(
+    ///         PostUpdate,
+    ///         SptsDioxusSet::ApplyMutations.before(TransformSystem::TransformPropagate),
+    ///     );
+    /// ```
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = schedule.intern();
+        self
+    }
+}
+
+/// The steps of a dioxus tick, chained in this order in the plugin's schedule.  Run the schedule
+/// again (or order your systems around these sets) to update the UI more than once a frame.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SptsDioxusSet {
+    /// Runs the systems that hooks queued.
+    DeferredSystems,
+    /// Creates / tears down roots, dispatches events to them and marks the scopes whose world
+    /// subscriptions changed as dirty.
+    Subscriptions,
+    /// Renders the dirty scopes of each root.
+    Render,
+    /// Applies the rendered mutations and the writes that hooks deferred to the world.
+    ApplyMutations,
+}
+
+/// Systems in [SptsDioxusSet::Subscriptions] that send [SptsDioxusEvent]s, they run before the
+/// events are dispatched to the roots.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct SptsDioxusEventSources;
+
+/// Schedule that the [SptsDioxusPlugin] runs in, the element events are forwarded in it.
+#[derive(Resource, Debug, Clone, Copy)]
+pub(crate) struct SptsDioxusSchedule(pub InternedScheduleLabel);
+
 impl<TT: SptsDioxusTemplateNode> Plugin for SptsDioxusPlugin<TT> {
     fn build(&self, app: &mut App) {
         app.init_non_send_resource::<SptsDioxusContext<TT>>()
-            .init_resource::<DeferredSystemRunQueue>()
-            .add_systems(Last, tick_dioxus_ui::<TT>);
+            .init_non_send_resource::<DeferredSystemRunQueue>()
+            .init_resource::<EventReaders>()
+            .init_resource::<RegisteredElementEvents>()
+            .init_resource::<SptsDioxusErrorPolicy>()
+            .init_resource::<SptsDioxusFocus>()
+            .init_resource::<SptsDioxusManagedPolicy>()
+            .init_resource::<SptsDioxusSnapshotComponents>()
+            .insert_resource(SptsDioxusSchedule(self.schedule))
+            .add_event::<SptsDioxusEvent>()
+            .add_event::<SptsDioxusError>()
+            .configure_sets(
+                self.schedule,
+                (
+                    SptsDioxusSet::DeferredSystems,
+                    SptsDioxusSet::Subscriptions,
+                    SptsDioxusSet::Render,
+                    SptsDioxusSet::ApplyMutations,
+                )
+                    .chain(),
+            )
+            .configure_sets(
+                self.schedule,
+                SptsDioxusEventSources.in_set(SptsDioxusSet::Subscriptions),
+            )
+            .add_systems(
+                self.schedule,
+                (
+                    run_deferred_systems.in_set(SptsDioxusSet::DeferredSystems),
+                    update_focus.in_set(SptsDioxusEventSources),
+                    (check_managed_entities::<TT>, update_subscriptions::<TT>)
+                        .chain()
+                        .after(SptsDioxusEventSources)
+                        .in_set(SptsDioxusSet::Subscriptions),
+                    render_roots::<TT>.in_set(SptsDioxusSet::Render),
+                    apply_mutations::<TT>.in_set(SptsDioxusSet::ApplyMutations),
+                ),
+            );
+        TT::register_events(app);
     }
 }
 
 pub struct SptsDioxusContext<TT: SptsDioxusTemplateNode> {
-    roots: HashMap<(Entity, SptsDioxusRootComponent), SptsDioxusRoot<TT>>,
-    subscriptions: EcsSubscriptions,
+    roots: EntityHashMap<SptsDioxusRoot<TT>>,
 }
 
 impl<TT: SptsDioxusTemplateNode> FromWorld for SptsDioxusContext<TT> {
     fn from_world(_world: &mut World) -> Self {
         Self {
-            roots: HashMap::default(),
-            subscriptions: EcsSubscriptions::default(),
+            roots: EntityHashMap::default(),
         }
     }
 }
 
-#[derive(Component, Deref, Hash, PartialEq, Eq, Clone, Copy)]
-pub struct SptsDioxusRootComponent(pub fn() -> Element);
-
 pub struct SptsDioxusRoot<TT: SptsDioxusTemplateNode> {
     virtual_dom: VirtualDom,
+    /// Component the `VirtualDom` was created from.
+    component: SptsDioxusRootComponent,
+    /// Gives the hooks access to the world while the root renders / handles events.
+    world_scope: WorldScope,
+    /// Shared with the hooks, which unsubscribe when their scope is dropped.
+    subscriptions: SharedEcsSubscriptions,
+    /// Provided as a root context, applied after each render.
+    world_writes: DeferredWorldWrites,
     el_to_entity: HashMap<ElementId, Entity>,
     entity_to_el: EntityHashMap<ElementId>,
     entity_refs: EntityHashMap<Signal<Option<Entity>>>,
+    /// Element entity to the entity of the observer attached with the `observe` attribute.
+    observers: EntityHashMap<Entity>,
+    /// Attributes applied to each element, for snapshots.
+    attributes: EntityHashMap<BTreeMap<&'static str, AttributeValue>>,
+    /// Converted templates keyed on the template's identity so each template is only converted
+    /// once, rather than for every node that's loaded from it.
+    templates: HashMap<Template, BevyTemplate<TT>>,
+    /// Rendered in the `Render` set, applied in the `ApplyMutations` set.
+    pending_mutations: SptsDioxusMutationLog,
+    /// Set once the root entity has a `SptsDioxusRecordMutations`.
+    mutation_log: Option<SptsDioxusMutationLog>,
     pd: PhantomData<TT>,
     needs_rebuild: bool,
+    /// Set when a managed entity was changed outside of dioxus and the
+    /// `SptsDioxusManagedPolicy` is `Rebuild`.
+    needs_teardown: bool,
 }
 
 impl<TT: SptsDioxusTemplateNode> SptsDioxusRoot<TT> {
     fn new(root_component: SptsDioxusRootComponent) -> Self {
+        let virtual_dom = root_component.new_virtual_dom();
+        let world_scope = WorldScope::default();
+        let subscriptions = SharedEcsSubscriptions::default();
+        let world_writes = DeferredWorldWrites::default();
+        virtual_dom.provide_root_context(EcsContext::<TT>::new(
+            world_scope.clone(),
+            subscriptions.clone(),
+        ));
+        virtual_dom.provide_root_context(world_writes.clone());
         Self {
-            virtual_dom: VirtualDom::new(root_component.0),
+            virtual_dom,
+            world_writes,
+            component: root_component,
+            world_scope,
+            subscriptions,
             el_to_entity: HashMap::new(),
             entity_to_el: EntityHashMap::default(),
             entity_refs: EntityHashMap::default(),
+            observers: EntityHashMap::default(),
+            attributes: EntityHashMap::default(),
+            templates: HashMap::new(),
+            pending_mutations: SptsDioxusMutationLog::default(),
+            mutation_log: None,
             pd: PhantomData,
             needs_rebuild: true,
+            needs_teardown: false,
         }
     }
 }
 
 pub mod prelude {
-    pub use super::{SptsDioxusContext, SptsDioxusPlugin, SptsDioxusRoot, SptsDioxusRootComponent};
+    pub use super::{
+        SptsDioxusContext, SptsDioxusPlugin, SptsDioxusRoot, SptsDioxusRootComponent,
+        SptsDioxusSet,
+    };
     pub use crate::adapter::*;
+    pub use crate::deferred_system::*;
     pub use crate::ecs_hooks::*;
+    pub use crate::error::*;
+    pub use crate::events::{
+        element_event_listener, register_element_event, SptsDioxusElementEvent, SptsDioxusEvent,
+        SptsDioxusEventListeners, SptsDioxusObserver,
+    };
+    pub use crate::focus::{
+        SptsDioxusFocus, SptsDioxusFocusData, SptsDioxusKey, SptsDioxusKeyData, SptsDioxusTabIndex,
+    };
+    pub use crate::managed::{
+        SptsDioxusManaged, SptsDioxusManagedPolicy, WithSptsDioxusManaged,
+        WithoutSptsDioxusManaged,
+    };
+    pub use crate::hooks::use_commands::{UseCommands, UseResourceMut};
+    pub use crate::hooks::use_entity::EntitySignal;
+    pub use crate::hooks::use_focus::UseFocus;
+    pub use crate::mutation_log::{
+        SptsDioxusMutation, SptsDioxusMutationLog, SptsDioxusRecordMutations,
+        SptsDioxusRecordedValue,
+    };
+    pub use crate::mutations::SptsDioxusMutationSummary;
+    pub use crate::snapshot::{
+        snapshot_root, SptsDioxusSnapshotAttributes, SptsDioxusSnapshotComponents,
+    };
+    #[cfg(feature = "testing")]
+    pub use crate::testing::SptsDioxusTestApp;
     pub use crate::utils::*;
+    #[cfg(feature = "bevy_picking")]
+    pub use bevy_picking;
+    pub use bevy_utils::tracing;
     pub use dioxus;
     pub use dioxus::prelude::{Event as DioxusEvent, *};
 }
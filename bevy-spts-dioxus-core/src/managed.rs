@@ -0,0 +1,123 @@
+use bevy_ecs::{
+    component::Component,
+    entity::{Entities, Entity},
+    event::EventWriter,
+    query::{Changed, With, Without},
+    removal_detection::RemovedComponents,
+    system::{NonSendMut, Query, Res, Resource},
+};
+use bevy_hierarchy::Parent;
+use dioxus::dioxus_core::ElementId;
+
+use crate::{
+    adapter::SptsDioxusTemplateNode,
+    error::{SptsDioxusError, SptsDioxusErrorKind, SptsDioxusErrorPolicy},
+    SptsDioxusContext, SptsDioxusRootComponent,
+};
+
+/// Inserted on every entity that dioxus spawned, despawning or re-parenting them outside of
+/// dioxus is reported as a [SptsDioxusError], see [SptsDioxusManagedPolicy].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SptsDioxusManaged {
+    /// Entity with the `SptsDioxusRootComponent` that spawned the entity.
+    pub root: Entity,
+    /// `None` for template nodes that dioxus hasn't assigned an id to, they're only reachable
+    /// through the template's root.
+    pub element: Option<ElementId>,
+    /// Element tag, [SptsDioxusManaged::TEXT] or [SptsDioxusManaged::PLACEHOLDER].
+    pub tag: &'static str,
+}
+
+impl SptsDioxusManaged {
+    pub const TEXT: &'static str = "#text";
+    pub const PLACEHOLDER: &'static str = "#placeholder";
+}
+
+/// Filter for entities spawned by dioxus.
+pub type WithSptsDioxusManaged = With<SptsDioxusManaged>;
+/// Filter for entities that dioxus didn't spawn, i.e. `Query<&mut Transform,
+/// WithoutSptsDioxusManaged>` in systems that shouldn't move dioxus' entities.
+pub type WithoutSptsDioxusManaged = Without<SptsDioxusManaged>;
+
+/// What happens to a root when one of its [SptsDioxusManaged] entities is despawned or
+/// re-parented outside of dioxus.  Either way a [SptsDioxusError] is reported according to the
+/// [SptsDioxusErrorPolicy].
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SptsDioxusManagedPolicy {
+    /// Keep the root, later mutations on the entity fail.
+    #[default]
+    Report,
+    /// Tear down the root and render it from scratch, losing its state.
+    Rebuild,
+}
+
+/// Reports managed entities that were despawned or re-parented since the last run.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn check_managed_entities<TT: SptsDioxusTemplateNode>(
+    mut context: NonSendMut<SptsDioxusContext<TT>>,
+    mut removed: RemovedComponents<SptsDioxusManaged>,
+    mut unparented: RemovedComponents<Parent>,
+    reparented: Query<Entity, (WithSptsDioxusManaged, Changed<Parent>)>,
+    managed: Query<(&SptsDioxusManaged, Option<&Parent>)>,
+    root_components: Query<(), With<SptsDioxusRootComponent>>,
+    entities: &Entities,
+    policy: Res<SptsDioxusManagedPolicy>,
+    error_policy: Res<SptsDioxusErrorPolicy>,
+    mut errors: EventWriter<SptsDioxusError>,
+) {
+    let mut tampered = Vec::new();
+
+    // Dioxus forgets the entities it despawns, so the ones it still knows about were despawned
+    // by someone else.  Despawning the root takes its entities with it, that's not an error.
+    for entity in removed.read() {
+        if entities.contains(entity) {
+            continue;
+        }
+        let root = context.roots.iter().find_map(|(root_entity, root)| {
+            let element = root.entity_to_el.get(&entity)?;
+            root_components
+                .contains(*root_entity)
+                .then_some((*root_entity, *element))
+        });
+        if let Some((root, element)) = root {
+            tampered.push(SptsDioxusError {
+                root,
+                element: Some(element),
+                mutation: "managed",
+                kind: SptsDioxusErrorKind::EntityDespawned(entity),
+            });
+        }
+    }
+
+    // Dioxus only parents its entities to the root or to other entities of the same root.
+    for entity in reparented.iter().chain(unparented.read()) {
+        let Ok((entity_managed, parent)) = managed.get(entity) else {
+            continue;
+        };
+        let parent = parent.map(Parent::get);
+        let parent_is_managed = parent.is_some_and(|parent| {
+            parent == entity_managed.root
+                || managed
+                    .get(parent)
+                    .is_ok_and(|(parent_managed, _)| parent_managed.root == entity_managed.root)
+        });
+        if !parent_is_managed {
+            tampered.push(SptsDioxusError {
+                root: entity_managed.root,
+                element: entity_managed.element,
+                mutation: "managed",
+                kind: SptsDioxusErrorKind::Reparented { entity, parent },
+            });
+        }
+    }
+
+    for error in tampered {
+        if *policy == SptsDioxusManagedPolicy::Rebuild {
+            if let Some(root) = context.roots.get_mut(&error.root) {
+                root.needs_teardown = true;
+            }
+        }
+        error_policy.report(&error);
+        errors.send(error);
+    }
+}
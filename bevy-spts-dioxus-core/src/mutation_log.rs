@@ -0,0 +1,377 @@
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use bevy_utils::HashMap;
+use dioxus::{
+    dioxus_core::{AttributeValue, ElementId, WriteMutations},
+    prelude::Template,
+};
+
+use crate::{adapter::SptsDioxusTemplateNode, mutations::MutationApplier, SptsDioxusContext};
+
+/// Insert on an entity with a `SptsDioxusRootComponent` to record the mutations applied to the
+/// root into a [SptsDioxusMutationLog], take it with [SptsDioxusContext::take_mutation_log].
+/// Insert it along with the root component to record from the first render, otherwise the
+/// log can't be replayed on its own.  Removing it stops recording, the log recorded so far can
+/// still be taken.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct SptsDioxusRecordMutations;
+
+/// `WriteMutations` calls that a root's `MutationApplier` received, in order.  Replay it with
+/// [SptsDioxusMutationLog::replay] to reproduce what the root spawned without the game logic
+/// or dioxus diffing.
+///
+/// With the `serialize` feature it can be serialized (i.e. to RON), attribute values that aren't
+/// text, numbers or bools (i.e. components passed with `WA(..)`) are left out and skipped when
+/// the log is replayed.  Deserializing leaks the templates, paths and names as dioxus needs them
+/// to be `'static`.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct SptsDioxusMutationLog {
+    /// Templates loaded by [SptsDioxusMutation::LoadTemplate], each template is stored once.
+    pub templates: Vec<Template>,
+    pub mutations: Vec<SptsDioxusMutation>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    template_indices: HashMap<Template, usize>,
+}
+
+// Aliases so serde doesn't borrow the fields from the deserializer's input, they're leaked.
+type StaticStr = &'static str;
+type StaticPath = &'static [u8];
+
+/// One `WriteMutations` call, see [SptsDioxusMutationLog].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum SptsDioxusMutation {
+    AppendChildren {
+        id: ElementId,
+        m: usize,
+    },
+    AssignNodeId {
+        #[cfg_attr(feature = "serialize", serde(deserialize_with = "leaky::bytes"))]
+        path: StaticPath,
+        id: ElementId,
+    },
+    CreatePlaceholder {
+        id: ElementId,
+    },
+    CreateTextNode {
+        value: String,
+        id: ElementId,
+    },
+    LoadTemplate {
+        /// Index into [SptsDioxusMutationLog::templates].
+        template: usize,
+        index: usize,
+        id: ElementId,
+    },
+    ReplaceNodeWith {
+        id: ElementId,
+        m: usize,
+    },
+    ReplacePlaceholderWithNodes {
+        #[cfg_attr(feature = "serialize", serde(deserialize_with = "leaky::bytes"))]
+        path: StaticPath,
+        m: usize,
+    },
+    InsertNodesAfter {
+        id: ElementId,
+        m: usize,
+    },
+    InsertNodesBefore {
+        id: ElementId,
+        m: usize,
+    },
+    SetAttribute {
+        #[cfg_attr(feature = "serialize", serde(deserialize_with = "leaky::str"))]
+        name: StaticStr,
+        #[cfg_attr(feature = "serialize", serde(deserialize_with = "leaky::option_str"))]
+        ns: Option<StaticStr>,
+        value: SptsDioxusRecordedValue,
+        id: ElementId,
+    },
+    SetNodeText {
+        value: String,
+        id: ElementId,
+    },
+    CreateEventListener {
+        #[cfg_attr(feature = "serialize", serde(deserialize_with = "leaky::str"))]
+        name: StaticStr,
+        id: ElementId,
+    },
+    RemoveEventListener {
+        #[cfg_attr(feature = "serialize", serde(deserialize_with = "leaky::str"))]
+        name: StaticStr,
+        id: ElementId,
+    },
+    RemoveNode {
+        id: ElementId,
+    },
+    PushRoot {
+        id: ElementId,
+    },
+}
+
+/// Attribute value of a [SptsDioxusMutation::SetAttribute].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum SptsDioxusRecordedValue {
+    Text(String),
+    Float(f64),
+    Int(i64),
+    Bool(bool),
+    None,
+    /// `AttributeValue::Any` (or a listener), only kept in memory.  `None` once the log has been
+    /// serialized, the attribute is skipped when it's replayed.
+    Any(#[cfg_attr(feature = "serialize", serde(skip))] Option<AttributeValue>),
+}
+
+impl From<&AttributeValue> for SptsDioxusRecordedValue {
+    fn from(value: &AttributeValue) -> Self {
+        match value {
+            AttributeValue::Text(text) => Self::Text(text.clone()),
+            AttributeValue::Float(float) => Self::Float(*float),
+            AttributeValue::Int(int) => Self::Int(*int),
+            AttributeValue::Bool(bool) => Self::Bool(*bool),
+            AttributeValue::None => Self::None,
+            value => Self::Any(Some(value.clone())),
+        }
+    }
+}
+
+impl SptsDioxusRecordedValue {
+    fn to_attribute_value(&self) -> Option<AttributeValue> {
+        Some(match self {
+            Self::Text(text) => AttributeValue::Text(text.clone()),
+            Self::Float(float) => AttributeValue::Float(*float),
+            Self::Int(int) => AttributeValue::Int(*int),
+            Self::Bool(bool) => AttributeValue::Bool(*bool),
+            Self::None => AttributeValue::None,
+            Self::Any(value) => value.clone()?,
+        })
+    }
+}
+
+impl SptsDioxusMutationLog {
+    fn push(&mut self, mutation: SptsDioxusMutation) {
+        self.mutations.push(mutation);
+    }
+
+    fn template_index(&mut self, template: Template) -> usize {
+        // The indices aren't serialized, rebuild them for a deserialized log.
+        if self.template_indices.len() != self.templates.len() {
+            self.template_indices = self
+                .templates
+                .iter()
+                .copied()
+                .enumerate()
+                .map(|(index, template)| (template, index))
+                .collect();
+        }
+        *self.template_indices.entry(template).or_insert_with(|| {
+            self.templates.push(template);
+            self.templates.len() - 1
+        })
+    }
+
+    /// Applies the mutations to a new root entity in `world`, with a fresh `MutationApplier`.
+    /// Returns the root entity, the rendered entities are spawned as its children.
+    pub fn replay<TT: SptsDioxusTemplateNode>(&self, world: &mut World) -> Entity {
+        let root_entity = world.spawn_empty().id();
+        let mut el_to_entity = Default::default();
+        let mut entity_to_el = Default::default();
+        let mut entity_refs = Default::default();
+        let mut observers = Default::default();
+        let mut templates = Default::default();
+        let mut mutation_applier = MutationApplier::<TT>::new(
+            &mut el_to_entity,
+            &mut entity_to_el,
+            &mut entity_refs,
+            &mut observers,
+            None,
+            &mut templates,
+            root_entity,
+            world,
+        );
+        self.apply_to(&mut mutation_applier);
+        root_entity
+    }
+
+    /// Appends the mutations of `other`, i.e. a log taken after this one to replay both.
+    pub fn append(&mut self, other: &SptsDioxusMutationLog) {
+        for mutation in &other.mutations {
+            let mutation = match mutation {
+                SptsDioxusMutation::LoadTemplate {
+                    template,
+                    index,
+                    id,
+                } => SptsDioxusMutation::LoadTemplate {
+                    template: self.template_index(other.templates[*template]),
+                    index: *index,
+                    id: *id,
+                },
+                mutation => mutation.clone(),
+            };
+            self.push(mutation);
+        }
+    }
+
+    pub(crate) fn apply_to(&self, to: &mut impl WriteMutations) {
+        for mutation in &self.mutations {
+            self.apply(mutation, to);
+        }
+    }
+
+    fn apply(&self, mutation: &SptsDioxusMutation, to: &mut impl WriteMutations) {
+        match mutation {
+            SptsDioxusMutation::AppendChildren { id, m } => to.append_children(*id, *m),
+            SptsDioxusMutation::AssignNodeId { path, id } => to.assign_node_id(path, *id),
+            SptsDioxusMutation::CreatePlaceholder { id } => to.create_placeholder(*id),
+            SptsDioxusMutation::CreateTextNode { value, id } => to.create_text_node(value, *id),
+            SptsDioxusMutation::LoadTemplate {
+                template,
+                index,
+                id,
+            } => to.load_template(self.templates[*template], *index, *id),
+            SptsDioxusMutation::ReplaceNodeWith { id, m } => to.replace_node_with(*id, *m),
+            SptsDioxusMutation::ReplacePlaceholderWithNodes { path, m } => {
+                to.replace_placeholder_with_nodes(path, *m)
+            }
+            SptsDioxusMutation::InsertNodesAfter { id, m } => to.insert_nodes_after(*id, *m),
+            SptsDioxusMutation::InsertNodesBefore { id, m } => to.insert_nodes_before(*id, *m),
+            SptsDioxusMutation::SetAttribute {
+                name,
+                ns,
+                value,
+                id,
+            } => {
+                if let Some(value) = value.to_attribute_value() {
+                    to.set_attribute(name, *ns, &value, *id);
+                }
+            }
+            SptsDioxusMutation::SetNodeText { value, id } => to.set_node_text(value, *id),
+            SptsDioxusMutation::CreateEventListener { name, id } => {
+                to.create_event_listener(name, *id)
+            }
+            SptsDioxusMutation::RemoveEventListener { name, id } => {
+                to.remove_event_listener(name, *id)
+            }
+            SptsDioxusMutation::RemoveNode { id } => to.remove_node(*id),
+            SptsDioxusMutation::PushRoot { id } => to.push_root(*id),
+        }
+    }
+}
+
+impl<TT: SptsDioxusTemplateNode> SptsDioxusContext<TT> {
+    /// Takes the mutations recorded for the root on `root_entity` so far, see
+    /// [SptsDioxusRecordMutations].  Recording continues into a new log that starts where this
+    /// one ends, so it can't be replayed on its own: [SptsDioxusMutationLog::append] it to the
+    /// logs taken before it to replay them together.
+    pub fn take_mutation_log(&mut self, root_entity: Entity) -> Option<SptsDioxusMutationLog> {
+        self.roots.get_mut(&root_entity)?.mutation_log.take()
+    }
+}
+
+/// Roots render into a log, which is applied with a `MutationApplier` afterwards.
+impl WriteMutations for SptsDioxusMutationLog {
+    fn append_children(&mut self, id: ElementId, m: usize) {
+        self.push(SptsDioxusMutation::AppendChildren { id, m });
+    }
+
+    fn assign_node_id(&mut self, path: &'static [u8], id: ElementId) {
+        self.push(SptsDioxusMutation::AssignNodeId { path, id });
+    }
+
+    fn create_placeholder(&mut self, id: ElementId) {
+        self.push(SptsDioxusMutation::CreatePlaceholder { id });
+    }
+
+    fn create_text_node(&mut self, value: &str, id: ElementId) {
+        self.push(SptsDioxusMutation::CreateTextNode {
+            value: value.to_string(),
+            id,
+        });
+    }
+
+    fn load_template(&mut self, template: Template, index: usize, id: ElementId) {
+        let template_index = self.template_index(template);
+        self.push(SptsDioxusMutation::LoadTemplate {
+            template: template_index,
+            index,
+            id,
+        });
+    }
+
+    fn replace_node_with(&mut self, id: ElementId, m: usize) {
+        self.push(SptsDioxusMutation::ReplaceNodeWith { id, m });
+    }
+
+    fn replace_placeholder_with_nodes(&mut self, path: &'static [u8], m: usize) {
+        self.push(SptsDioxusMutation::ReplacePlaceholderWithNodes { path, m });
+    }
+
+    fn insert_nodes_after(&mut self, id: ElementId, m: usize) {
+        self.push(SptsDioxusMutation::InsertNodesAfter { id, m });
+    }
+
+    fn insert_nodes_before(&mut self, id: ElementId, m: usize) {
+        self.push(SptsDioxusMutation::InsertNodesBefore { id, m });
+    }
+
+    fn set_attribute(
+        &mut self,
+        name: &'static str,
+        ns: Option<&'static str>,
+        value: &AttributeValue,
+        id: ElementId,
+    ) {
+        self.push(SptsDioxusMutation::SetAttribute {
+            name,
+            ns,
+            value: value.into(),
+            id,
+        });
+    }
+
+    fn set_node_text(&mut self, value: &str, id: ElementId) {
+        self.push(SptsDioxusMutation::SetNodeText {
+            value: value.to_string(),
+            id,
+        });
+    }
+
+    fn create_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.push(SptsDioxusMutation::CreateEventListener { name, id });
+    }
+
+    fn remove_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.push(SptsDioxusMutation::RemoveEventListener { name, id });
+    }
+
+    fn remove_node(&mut self, id: ElementId) {
+        self.push(SptsDioxusMutation::RemoveNode { id });
+    }
+
+    fn push_root(&mut self, id: ElementId) {
+        self.push(SptsDioxusMutation::PushRoot { id });
+    }
+}
+
+/// Deserializers for the `'static` data that dioxus expects.
+#[cfg(feature = "serialize")]
+mod leaky {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<&'static str, D::Error> {
+        String::deserialize(deserializer).map(|string| &*Box::leak(string.into_boxed_str()))
+    }
+
+    pub fn option_str<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<&'static str>, D::Error> {
+        Option::<String>::deserialize(deserializer)
+            .map(|string| string.map(|string| &*Box::leak(string.into_boxed_str())))
+    }
+
+    pub fn bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<&'static [u8], D::Error> {
+        Vec::<u8>::deserialize(deserializer).map(|bytes| &*Box::leak(bytes.into_boxed_slice()))
+    }
+}
@@ -1,19 +1,29 @@
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, marker::PhantomData};
 
 use bevy_ecs::{
     entity::{Entity, EntityHashMap},
     system::{Query, SystemState},
+    component::Component,
     world::{Command, World},
 };
 use bevy_hierarchy::{BuildChildren, Children, DespawnRecursive, HierarchyQueryExt, Parent};
-use bevy_utils::HashMap;
+use bevy_utils::{hashbrown::hash_map::Entry, HashMap};
 use dioxus::{
     dioxus_core::{AttributeValue, ElementId, WriteMutations},
-    prelude::Template,
+    prelude::{Template, TemplateAttribute, TemplateNode},
     signals::{Signal, Writable},
 };
 
-use crate::{adapter::SptsDioxusTemplateNode, hooks::use_entity::EntitySignal};
+use bevy_utils::tracing::trace;
+
+use crate::{
+    adapter::{AttributeValueHelpers, SptsDioxusTemplateNode},
+    error::{SptsDioxusError, SptsDioxusErrorKind, SptsDioxusErrorPolicy},
+    events::{insert_event_listener, remove_event_listener, SptsDioxusObserver},
+    focus::SptsDioxusTabIndex,
+    managed::SptsDioxusManaged,
+    hooks::use_entity::EntitySignal,
+};
 
 pub struct MutationApplier<'a, TT: SptsDioxusTemplateNode> {
     el_to_entity: &'a mut HashMap<ElementId, Entity>,
@@ -21,41 +31,75 @@ pub struct MutationApplier<'a, TT: SptsDioxusTemplateNode> {
     /// Lookup for Entity Id References so we can set / unset it when the entity is mounted /
     /// unmounted.
     entity_refs: &'a mut EntityHashMap<Signal<Option<Entity>>>,
+    /// Observer entities attached to elements with the `observe` attribute.
+    observers: &'a mut EntityHashMap<Entity>,
+    /// Attributes applied to each element, only kept for snapshots.
+    attributes: Option<&'a mut EntityHashMap<BTreeMap<&'static str, AttributeValue>>>,
+    templates: &'a mut HashMap<Template, BevyTemplate<TT>>,
+    root_entity: Entity,
+    error_policy: SptsDioxusErrorPolicy,
     world: &'a mut World,
     stack: Vec<Entity>,
+    summary: SptsDioxusMutationSummary,
     pd: PhantomData<TT>,
 }
 
 impl<'a, TT: SptsDioxusTemplateNode> MutationApplier<'a, TT> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         el_to_entity: &'a mut HashMap<ElementId, Entity>,
         entity_to_el: &'a mut EntityHashMap<ElementId>,
         entity_refs: &'a mut EntityHashMap<Signal<Option<Entity>>>,
+        observers: &'a mut EntityHashMap<Entity>,
+        attributes: Option<&'a mut EntityHashMap<BTreeMap<&'static str, AttributeValue>>>,
+        templates: &'a mut HashMap<Template, BevyTemplate<TT>>,
         root_entity: Entity,
         world: &'a mut World,
     ) -> Self {
         el_to_entity.insert(ElementId(0), root_entity);
         entity_to_el.insert(root_entity, ElementId(0));
 
+        let error_policy = world
+            .get_resource::<SptsDioxusErrorPolicy>()
+            .copied()
+            .unwrap_or_default();
+
         Self {
             el_to_entity,
             entity_to_el,
             entity_refs,
+            observers,
+            attributes,
+            templates,
+            root_entity,
+            error_policy,
             world,
             stack: vec![root_entity],
+            summary: SptsDioxusMutationSummary::default(),
             pd: PhantomData,
         }
     }
 }
 
 impl<'a, TT: SptsDioxusTemplateNode> MutationApplier<'a, TT> {
+    /// Counts of the mutations applied so far.
+    pub fn summary(&self) -> SptsDioxusMutationSummary {
+        self.summary
+    }
+
     pub fn despawn_recursive(&mut self, entity: Entity) {
-        let mut ss: SystemState<Query<&Children>> = SystemState::new(self.world);
-        let query_children = ss.get_mut(self.world);
+        let world = &mut *self.world;
+        let mut ss: SystemState<Query<&Children>> = SystemState::new(world);
+        let query_children = ss.get_mut(world);
         for child in query_children.iter_descendants(entity) {
             if let Some(mut existing_entity_ref) = self.entity_refs.remove(&child) {
                 existing_entity_ref.set(None)
             }
+            // Bevy despawns the observers along with the entities they watch.
+            self.observers.remove(&child);
+            if let Some(attributes) = self.attributes.as_deref_mut() {
+                attributes.remove(&child);
+            }
             if let Some(existing_element_id) = self.entity_to_el.remove(&child) {
                 self.el_to_entity.remove(&existing_element_id);
             }
@@ -64,60 +108,276 @@ impl<'a, TT: SptsDioxusTemplateNode> MutationApplier<'a, TT> {
         if let Some(mut existing_entity_ref) = self.entity_refs.remove(&entity) {
             existing_entity_ref.set(None)
         }
+        self.observers.remove(&entity);
+        if let Some(attributes) = self.attributes.as_deref_mut() {
+            attributes.remove(&entity);
+        }
         if let Some(existing_element_id) = self.entity_to_el.remove(&entity) {
             self.el_to_entity.remove(&existing_element_id);
         }
 
-        DespawnRecursive { warn: true, entity }.apply(self.world);
+        DespawnRecursive { warn: true, entity }.apply(world);
+    }
+
+    /// Reports the error according to the [SptsDioxusErrorPolicy].
+    fn handle_error(
+        &mut self,
+        mutation: &'static str,
+        element: Option<ElementId>,
+        result: Result<(), SptsDioxusErrorKind>,
+    ) {
+        let Err(kind) = result else {
+            return;
+        };
+        let error = SptsDioxusError {
+            root: self.root_entity,
+            element,
+            mutation,
+            kind,
+        };
+        self.error_policy.report(&error);
+        self.world.send_event(error);
+    }
+
+    /// Despawns nodes taken from the stack that couldn't be inserted into the hierarchy.
+    fn handle_orphans<T>(&mut self, result: &Result<T, SptsDioxusErrorKind>, nodes: &[Entity]) {
+        if result.is_err() {
+            for node in nodes {
+                self.despawn_recursive(*node);
+            }
+        }
+    }
+
+    fn record_attribute(&mut self, entity: Entity, name: &'static str, value: &AttributeValue) {
+        let Some(attributes) = self.attributes.as_deref_mut() else {
+            return;
+        };
+        if matches!(value, AttributeValue::None) {
+            if let Some(attributes) = attributes.get_mut(&entity) {
+                attributes.remove(name);
+            }
+        } else {
+            attributes
+                .entry(entity)
+                .or_default()
+                .insert(name, value.clone());
+        }
+    }
+
+    /// Records the static attributes of `node` and marks the entity spawned for it as a
+    /// [SptsDioxusManaged] entity, then does the same for its children.  `nodes` are the entities
+    /// spawned for the template's nodes, see [SptsDioxusTemplateNode::try_spawn_nodes].
+    fn record_template_node(
+        &mut self,
+        node: &TemplateNode,
+        nodes: &mut impl Iterator<Item = Entity>,
+        element: Option<ElementId>,
+    ) {
+        let Some(entity) = nodes.next() else {
+            return;
+        };
+        let (tag, children): (&str, &[TemplateNode]) = match node {
+            TemplateNode::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let record_attributes = self.attributes.is_some();
+                for attr in attrs.iter().filter(|_| record_attributes) {
+                    if let TemplateAttribute::Static { name, value, .. } = attr {
+                        let value = AttributeValue::Text(value.to_string());
+                        self.record_attribute(entity, name, &value);
+                    }
+                }
+                (*tag, *children)
+            }
+            TemplateNode::Text { .. } => (SptsDioxusManaged::TEXT, &[]),
+            TemplateNode::Dynamic { .. } => (SptsDioxusManaged::PLACEHOLDER, &[]),
+        };
+        self.mark_managed(entity, element, tag);
+        for child in children {
+            self.record_template_node(child, nodes, None);
+        }
+    }
+
+    /// Marks `entity` as a [SptsDioxusManaged] entity with `tag`.
+    fn mark_managed(&mut self, entity: Entity, element: Option<ElementId>, tag: &'static str) {
+        if let Ok(mut entity_mut) = self.world.get_entity_mut(entity) {
+            entity_mut.insert(SptsDioxusManaged {
+                root: self.root_entity,
+                element,
+                tag,
+            });
+        }
+    }
+
+    /// Takes the last `m` nodes off of the stack, leaving the stack as it is if there are fewer.
+    fn take_nodes(&mut self, m: usize) -> Result<Vec<Entity>, SptsDioxusErrorKind> {
+        let found = self.stack.len();
+        if m > found {
+            return Err(SptsDioxusErrorKind::StackUnderflow { expected: m, found });
+        }
+        Ok(self.stack.split_off(found - m))
+    }
+
+    fn get_last_node(&self) -> Result<Entity, SptsDioxusErrorKind> {
+        self.stack
+            .last()
+            .copied()
+            .ok_or(SptsDioxusErrorKind::StackUnderflow {
+                expected: 1,
+                found: 0,
+            })
+    }
+
+    fn get_entity(&self, id: ElementId) -> Result<Entity, SptsDioxusErrorKind> {
+        let entity = *self
+            .el_to_entity
+            .get(&id)
+            .ok_or(SptsDioxusErrorKind::UnknownElementId(id))?;
+        if self.world.get_entity(entity).is_err() {
+            return Err(SptsDioxusErrorKind::EntityDespawned(entity));
+        }
+        Ok(entity)
+    }
+
+    fn get_entity_at_path(&self, root: Entity, path: &[u8]) -> Result<Entity, SptsDioxusErrorKind> {
+        let mut entity = root;
+        for index in path {
+            let index = *index as usize;
+            let children = self.world.get::<Children>(entity).ok_or(
+                SptsDioxusErrorKind::MissingComponent {
+                    entity,
+                    component: "Children",
+                },
+            )?;
+            entity = *children
+                .get(index)
+                .ok_or(SptsDioxusErrorKind::MissingChild { entity, index })?;
+        }
+        Ok(entity)
+    }
+
+    /// Inserts `nodes` into the parent of `sibling`, `offset` places after `sibling`.
+    fn insert_nodes_relative_to(
+        &mut self,
+        sibling: Entity,
+        offset: usize,
+        nodes: &[Entity],
+    ) -> Result<(), SptsDioxusErrorKind> {
+        let parent = self.world.get::<Parent>(sibling).map(Parent::get).ok_or(
+            SptsDioxusErrorKind::MissingComponent {
+                entity: sibling,
+                component: "Parent",
+            },
+        )?;
+        let index = self
+            .world
+            .get::<Children>(parent)
+            .and_then(|children| children.iter().position(|child| *child == sibling))
+            .ok_or(SptsDioxusErrorKind::NotAChild {
+                parent,
+                child: sibling,
+            })?;
+        self.world
+            .entity_mut(parent)
+            .insert_children(index + offset, nodes);
+        Ok(())
     }
 }
 
 impl<'a, TT: SptsDioxusTemplateNode> WriteMutations for MutationApplier<'a, TT> {
     fn append_children(&mut self, id: ElementId, m: usize) {
-        println!("WriteMutations::append_children(id: {id:?}, m: {m:?})");
-        let mut parent = self.world.entity_mut(self.el_to_entity[&id]);
-        for child in self.stack.drain((self.stack.len() - m)..) {
-            parent.add_child(child);
-        }
+        trace!(element = ?id, m, "append_children");
+        let result = self.take_nodes(m).and_then(|children| {
+            let result = self.get_entity(id).map(|parent| {
+                self.world.entity_mut(parent).add_children(&children);
+            });
+            self.handle_orphans(&result, &children);
+            result
+        });
+        self.handle_error("append_children", Some(id), result);
     }
 
     fn assign_node_id(&mut self, path: &'static [u8], id: ElementId) {
-        println!("WriteMutations::assign_node_id(path: {path:?}, id: {id:?})");
-        let mut entity = *self.stack.last().unwrap();
-        for index in path {
-            // let v = self.world.inspect_entity(entity);
-            // println!("\t entity {entity:?} {v:?}");
-            let entity_ref = self.world.entity(entity);
-            let children = entity_ref.get::<Children>().unwrap();
-            entity = children[*index as usize];
-        }
-        self.el_to_entity.insert(id, entity);
-        self.entity_to_el.insert(entity, id);
+        trace!(element = ?id, ?path, "assign_node_id");
+        let result = self.get_last_node().and_then(|root| {
+            let entity = self.get_entity_at_path(root, path)?;
+            self.el_to_entity.insert(id, entity);
+            self.entity_to_el.insert(entity, id);
+            if let Some(mut managed) = self.world.get_mut::<SptsDioxusManaged>(entity) {
+                managed.element = Some(id);
+            }
+            Ok(())
+        });
+        self.handle_error("assign_node_id", Some(id), result);
     }
 
     fn create_placeholder(&mut self, id: ElementId) {
-        println!("WriteMutations::create_placeholder(id: {id:?})");
-        let entity = self.world.spawn_empty().id();
+        let entity = TT::spawn_placeholder(self.world);
+        trace!(element = ?id, ?entity, "create_placeholder");
+        self.summary.created += 1;
+        self.mark_managed(entity, Some(id), SptsDioxusManaged::PLACEHOLDER);
         self.el_to_entity.insert(id, entity);
         self.entity_to_el.insert(entity, id);
         self.stack.push(entity);
     }
 
     fn create_text_node(&mut self, value: &str, id: ElementId) {
-        println!("WriteMutations::create_text_node(value: {value:?}, id: {id:?})");
-        todo!("create_text_node");
-        // let entity =
-        //     BevyTemplateNode::IntrinsicTextNode(Text::from_section(value, TextStyle::default()))
-        //         .spawn(self.world);
-        // self.el_to_entity.insert(id, entity);
-        // self.entity_to_el.insert(entity, id);
-        // self.stack.push(entity);
+        let entity = TT::spawn_text(self.world, value);
+        trace!(element = ?id, ?entity, value, "create_text_node");
+        self.summary.created += 1;
+        self.mark_managed(entity, Some(id), SptsDioxusManaged::TEXT);
+        self.el_to_entity.insert(id, entity);
+        self.entity_to_el.insert(entity, id);
+        self.stack.push(entity);
     }
 
     fn load_template(&mut self, template: Template, index: usize, id: ElementId) {
-        println!("WriteMutations::load_template(template: {template:?}, index: {index:?}, id: {id:?})");
-        let bevy_template = BevyTemplate::<TT>::from_dioxus(&template);
-        let entity = TT::spawn(&bevy_template.roots[index], self.world);
+        let world = &mut *self.world;
+        let missing_root = SptsDioxusErrorKind::MissingTemplateRoot {
+            index,
+            roots: template.roots.len(),
+        };
+        let result = template
+            .roots
+            .get(index)
+            .ok_or(missing_root.clone())
+            .and_then(|node| {
+                let bevy_template = match self.templates.entry(template) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(BevyTemplate::<TT>::try_from_dioxus(&template)?)
+                    }
+                };
+                let bevy_node = bevy_template.roots.get(index).ok_or(missing_root)?;
+                let mut nodes = Vec::new();
+                TT::try_spawn_nodes(bevy_node, world, &mut nodes).map(|entity| (node, entity, nodes))
+            });
+
+        // Dioxus still expects a node on the stack, spawn a placeholder in place of the template.
+        let entity = match result {
+            Ok((node, entity, nodes)) => {
+                self.record_template_node(node, &mut nodes.into_iter(), Some(id));
+                entity
+            }
+            Err(kind) => {
+                self.handle_error("load_template", Some(id), Err(kind));
+                let entity = TT::spawn_placeholder(self.world);
+                self.mark_managed(entity, Some(id), SptsDioxusManaged::PLACEHOLDER);
+                entity
+            }
+        };
+
+        trace!(
+            element = ?id,
+            ?entity,
+            template = template_name(&template, index),
+            index,
+            "load_template"
+        );
+        self.summary.created += 1;
 
         self.el_to_entity.insert(id, entity);
         self.entity_to_el.insert(entity, id);
@@ -125,68 +385,58 @@ impl<'a, TT: SptsDioxusTemplateNode> WriteMutations for MutationApplier<'a, TT>
     }
 
     fn replace_node_with(&mut self, id: ElementId, m: usize) {
-        println!("WriteMutations::replace_node_with(id: {id:?}, m: {m:?})");
-        let existing = self.el_to_entity[&id];
-        let existing_parent = self.world.entity(existing).get::<Parent>().unwrap().get();
-        let mut existing_parent = self.world.entity_mut(existing_parent);
-
-        let existing_index = existing_parent
-            .get::<Children>()
-            .unwrap()
-            .iter()
-            .position(|child| *child == existing)
-            .unwrap();
-        existing_parent
-            .insert_children(existing_index, &self.stack.split_off(self.stack.len() - m));
-
-        self.despawn_recursive(existing);
+        trace!(element = ?id, m, "replace_node_with");
+        let result = self.take_nodes(m).and_then(|nodes| {
+            let result = self.get_entity(id).and_then(|existing| {
+                let result = self.insert_nodes_relative_to(existing, 0, &nodes);
+                self.despawn_recursive(existing);
+                self.summary.removed += 1;
+                result
+            });
+            self.handle_orphans(&result, &nodes);
+            result
+        });
+        self.handle_error("replace_node_with", Some(id), result);
     }
 
     fn replace_placeholder_with_nodes(&mut self, path: &'static [u8], m: usize) {
-        println!("WriteMutations::replace_placeholder_with_nodes(path: {path:?}, m: {m:?})");
-        let mut existing = self.stack[self.stack.len() - m - 1];
-        for index in path {
-            existing = self.world.entity(existing).get::<Children>().unwrap()[*index as usize];
-        }
-        let existing_parent = self.world.entity(existing).get::<Parent>().unwrap().get();
-        let mut existing_parent = self.world.entity_mut(existing_parent);
-
-        let existing_index = existing_parent
-            .get::<Children>()
-            .unwrap()
-            .iter()
-            .position(|child| *child == existing)
-            .unwrap();
-        existing_parent
-            .insert_children(existing_index, &self.stack.split_off(self.stack.len() - m));
+        trace!(?path, m, "replace_placeholder_with_nodes");
+        let result = self.take_nodes(m).and_then(|nodes| {
+            let result = self.get_last_node().and_then(|root| {
+                let existing = self.get_entity_at_path(root, path)?;
+                let result = self.insert_nodes_relative_to(existing, 0, &nodes);
+                // The placeholder was only there to hold the position of the new nodes.
+                self.despawn_recursive(existing);
+                result
+            });
+            self.handle_orphans(&result, &nodes);
+            result
+        });
+        self.handle_error("replace_placeholder_with_nodes", None, result);
     }
 
     fn insert_nodes_after(&mut self, id: ElementId, m: usize) {
-        println!("WriteMutations::insert_nodes_after(id: {id:?}, m: {m:?})");
-        let entity = self.el_to_entity[&id];
-        let parent = self.world.entity(entity).get::<Parent>().unwrap().get();
-        let mut parent = self.world.entity_mut(parent);
-        let index = parent
-            .get::<Children>()
-            .unwrap()
-            .iter()
-            .position(|child| *child == entity)
-            .unwrap();
-        parent.insert_children(index + 1, &self.stack.split_off(self.stack.len() - m));
+        trace!(element = ?id, m, "insert_nodes_after");
+        let result = self.take_nodes(m).and_then(|nodes| {
+            let result = self
+                .get_entity(id)
+                .and_then(|entity| self.insert_nodes_relative_to(entity, 1, &nodes));
+            self.handle_orphans(&result, &nodes);
+            result
+        });
+        self.handle_error("insert_nodes_after", Some(id), result);
     }
 
     fn insert_nodes_before(&mut self, id: ElementId, m: usize) {
-        println!("WriteMutations::insert_nodes_before(id: {id:?}, m: {m:?})");
-        let existing = self.el_to_entity[&id];
-        let parent = self.world.entity(existing).get::<Parent>().unwrap().get();
-        let mut parent = self.world.entity_mut(parent);
-        let index = parent
-            .get::<Children>()
-            .unwrap()
-            .iter()
-            .position(|child| *child == existing)
-            .unwrap();
-        parent.insert_children(index, &self.stack.split_off(self.stack.len() - m));
+        trace!(element = ?id, m, "insert_nodes_before");
+        let result = self.take_nodes(m).and_then(|nodes| {
+            let result = self
+                .get_entity(id)
+                .and_then(|entity| self.insert_nodes_relative_to(entity, 0, &nodes));
+            self.handle_orphans(&result, &nodes);
+            result
+        });
+        self.handle_error("insert_nodes_before", Some(id), result);
     }
 
     fn set_attribute(
@@ -196,60 +446,163 @@ impl<'a, TT: SptsDioxusTemplateNode> WriteMutations for MutationApplier<'a, TT>
         value: &AttributeValue,
         id: ElementId,
     ) {
-        let entity = self.el_to_entity[&id];
-
-        println!("set_attribute {name} {ns:?} {id:?}");
-        match name {
-            "entity" => {
-                let AttributeValue::Any(boxed_any) = value else {
-                    bevy_utils::tracing::warn!("bevy_spts_dioxus: Value passed to 'entity' attribute that wasn't an 'EntitySignal'.");
-                    return;
-                };
-                let Some(entity_signal) = boxed_any.as_any().downcast_ref::<EntitySignal>() else {
-                    bevy_utils::tracing::warn!("bevy_spts_dioxus: Value passed to 'entity' attribute that wasn't an 'EntitySignal'.");
-                    return;
-                };
-                let mut entity_signal = *entity_signal;
-                entity_signal.set(Some(entity));
-                self.entity_refs.insert(entity, *entity_signal);
-            }
-            name => {
-                TT::apply_attribute(self.world, entity, name, value);
+        let result = self.get_entity(id).and_then(|entity| {
+            trace!(element = ?id, ?entity, attribute = name, ?ns, ?value, "set_attribute");
+            let result = match name {
+                "entity" if matches!(value, AttributeValue::None) => {
+                    if let Some(mut existing_entity_ref) = self.entity_refs.remove(&entity) {
+                        existing_entity_ref.set(None);
+                    }
+                    Ok(())
+                }
+                "entity" => {
+                    let entity_signal = value.as_concrete::<EntitySignal>().ok_or(
+                        SptsDioxusErrorKind::InvalidAttributeValue {
+                            name,
+                            expected: "EntitySignal",
+                        },
+                    )?;
+                    let mut entity_signal = *entity_signal;
+                    entity_signal.set(Some(entity));
+                    self.entity_refs.insert(entity, *entity_signal);
+                    Ok(())
+                }
+                "observe" => {
+                    let observer = match value {
+                        AttributeValue::None => None,
+                        value => Some(value.as_concrete::<SptsDioxusObserver>().ok_or(
+                            SptsDioxusErrorKind::InvalidAttributeValue {
+                                name,
+                                expected: "SptsDioxusObserver",
+                            },
+                        )?),
+                    };
+                    if let Some(existing_observer) = self.observers.remove(&entity) {
+                        self.world.despawn(existing_observer);
+                    }
+                    if let Some(observer) = observer {
+                        let observer_entity = observer.spawn(self.world, entity);
+                        self.observers.insert(entity, observer_entity);
+                    }
+                    Ok(())
+                }
+                "tabindex" => {
+                    let mut entity_mut = self.world.entity_mut(entity);
+                    match value {
+                        AttributeValue::None => {
+                            entity_mut.remove::<SptsDioxusTabIndex>();
+                        }
+                        AttributeValue::Int(tab_index) => {
+                            entity_mut.insert(SptsDioxusTabIndex(*tab_index as i32));
+                        }
+                        _ => {
+                            return Err(SptsDioxusErrorKind::InvalidAttributeValue {
+                                name,
+                                expected: "integer",
+                            })
+                        }
+                    }
+                    Ok(())
+                }
+                name if matches!(value, AttributeValue::None) => {
+                    TT::try_remove_attribute(self.world, entity, name)
+                }
+                name => TT::try_apply_attribute(self.world, entity, name, value),
+            };
+            if result.is_ok() {
+                if !matches!(name, "entity" | "observe" | "tabindex") {
+                    self.summary.updated += 1;
+                }
+                self.record_attribute(entity, name, value);
             }
-        }
+            result
+        });
+        self.handle_error("set_attribute", Some(id), result);
     }
 
-    fn set_node_text(&mut self, _value: &str, _id: ElementId) {
-        todo!("set_node_text");
+    fn set_node_text(&mut self, value: &str, id: ElementId) {
+        let result = self.get_entity(id).map(|entity| {
+            trace!(element = ?id, ?entity, value, "set_node_text");
+            self.summary.updated += 1;
+            TT::set_text(self.world, entity, value);
+        });
+        self.handle_error("set_node_text", Some(id), result);
     }
 
-    fn create_event_listener(&mut self, _name: &'static str, _id: ElementId) {
-        todo!("create_event_listener");
-        // insert_event_listener(
-        //     &name,
-        //     self.world
-        //         .entity_mut(self.el_to_entity[&id]),
-        // );
+    fn create_event_listener(&mut self, name: &'static str, id: ElementId) {
+        trace!(element = ?id, name, "create_event_listener");
+        let result = self
+            .get_entity(id)
+            .map(|entity| insert_event_listener(name, self.world.entity_mut(entity)));
+        self.handle_error("create_event_listener", Some(id), result);
     }
 
-    fn remove_event_listener(&mut self, _name: &'static str, _id: ElementId) {
-        todo!("remove_event_listener");
-        // remove_event_listener(
-        //     &name,
-        //     self.world
-        //         .entity_mut(self.el_to_entity[&id]),
-        // );
+    fn remove_event_listener(&mut self, name: &'static str, id: ElementId) {
+        trace!(element = ?id, name, "remove_event_listener");
+        let result = self
+            .get_entity(id)
+            .map(|entity| remove_event_listener(name, self.world.entity_mut(entity)));
+        self.handle_error("remove_event_listener", Some(id), result);
     }
 
     fn remove_node(&mut self, id: ElementId) {
-        println!("WriteMutations::remove_node(id: {id:?})");
-        let entity = self.el_to_entity[&id];
-        self.despawn_recursive(entity);
+        let result = self.get_entity(id);
+        if let Some(entity) = self.el_to_entity.get(&id).copied() {
+            trace!(element = ?id, ?entity, "remove_node");
+            self.summary.removed += 1;
+            // Still forget the entity if it was despawned outside of dioxus.
+            self.despawn_recursive(entity);
+        }
+        self.handle_error("remove_node", Some(id), result.map(|_| ()));
     }
 
     fn push_root(&mut self, id: ElementId) {
-        println!("WriteMutations::push_root(id: {id:?})");
-        self.stack.push(self.el_to_entity[&id]);
+        trace!(element = ?id, "push_root");
+        // Dioxus still expects a node on the stack, push a placeholder if the node is missing.
+        // It takes over the ElementId so it's despawned when dioxus removes the node.
+        let entity = match self.get_entity(id) {
+            Ok(entity) => entity,
+            Err(kind) => {
+                self.handle_error("push_root", Some(id), Err(kind));
+                let entity = TT::spawn_placeholder(self.world);
+                self.mark_managed(entity, Some(id), SptsDioxusManaged::PLACEHOLDER);
+                if let Some(missing) = self.el_to_entity.insert(id, entity) {
+                    self.entity_to_el.remove(&missing);
+                }
+                self.entity_to_el.insert(entity, id);
+                entity
+            }
+        };
+        self.stack.push(entity);
+    }
+}
+
+/// Insert on an entity with a `SptsDioxusRootComponent` to log a summary of the mutations
+/// applied to the root each frame (at debug level).  Updated with the counts from the last
+/// frame that had mutations.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SptsDioxusMutationSummary {
+    /// Templates, text nodes and placeholders spawned.
+    pub created: usize,
+    /// Nodes removed or replaced.
+    pub removed: usize,
+    /// Attributes that the adapter applied or removed and text set, the built-in `entity`,
+    /// `observe` and `tabindex` attributes and failed sets aren't counted.
+    pub updated: usize,
+}
+
+impl SptsDioxusMutationSummary {
+    pub fn is_empty(&self) -> bool {
+        self.created == 0 && self.removed == 0 && self.updated == 0
+    }
+}
+
+/// Templates don't have names in dioxus 0.6 so the root node's tag stands in for one.
+fn template_name(template: &Template, index: usize) -> &'static str {
+    match template.roots.get(index) {
+        Some(TemplateNode::Element { tag, .. }) => tag,
+        Some(TemplateNode::Text { .. }) => "#text",
+        Some(TemplateNode::Dynamic { .. }) | None => "#dynamic",
     }
 }
 
@@ -259,13 +612,13 @@ pub struct BevyTemplate<TT: SptsDioxusTemplateNode> {
 }
 
 impl<TT: SptsDioxusTemplateNode> BevyTemplate<TT> {
-    fn from_dioxus(template: &Template) -> Self {
-        Self {
+    fn try_from_dioxus(template: &Template) -> Result<Self, SptsDioxusErrorKind> {
+        Ok(Self {
             roots: template
                 .roots
                 .iter()
-                .map(|node| TT::from_dioxus(node))
-                .collect(),
-        }
+                .map(|node| TT::try_from_dioxus(node))
+                .collect::<Result<_, _>>()?,
+        })
     }
 }
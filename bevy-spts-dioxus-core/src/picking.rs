@@ -0,0 +1,31 @@
+use bevy_ecs::entity::Entity;
+use bevy_picking::events::{
+    Click, Down, Drag, DragDrop, DragEnd, DragEnter, DragLeave, DragOver, DragStart, Move, Out,
+    Over, Pointer, Up,
+};
+
+use crate::events::SptsDioxusElementEvent;
+
+/// Pointer events are dispatched to the entity that was hit, bubbling up the bevy hierarchy to the
+/// closest dioxus managed entity and then up the dioxus elements.
+///
+/// `bevy_picking` triggers them as observers (bubbling up the bevy hierarchy) and sends them with
+/// an `EventWriter`, only the latter is forwarded.
+macro_rules! impl_pointer_element_event {
+    ($($event:ty),* $(,)?) => {
+        $(
+            impl SptsDioxusElementEvent for Pointer<$event> {
+                fn target(&self) -> Option<Entity> {
+                    Some(self.target)
+                }
+
+                const FORWARD_TRIGGERS: bool = false;
+            }
+        )*
+    };
+}
+
+impl_pointer_element_event!(
+    Click, Down, Up, Move, Over, Out, DragStart, Drag, DragEnd, DragEnter, DragOver, DragLeave,
+    DragDrop,
+);
@@ -0,0 +1,131 @@
+use std::{any::Any, sync::Arc};
+
+use bevy_ecs::component::Component;
+use dioxus::{
+    dioxus_core::{ScopeId, VirtualDom},
+    prelude::{consume_context, Element},
+};
+
+/// Mounts a dioxus component on this entity, the elements it renders are spawned as children
+/// of this entity.
+///
+/// # Example
+///
+/// ```ignore
+/// #[component]
+/// fn HealthBar(health: f32) -> Element { ... }
+///
+/// commands.spawn(SptsDioxusRootComponent::new(root));
+/// // Inserting it again with different props re-renders the health bar.
+/// commands
+///     .entity(enemy)
+///     .insert(SptsDioxusRootComponent::with_props(HealthBar, HealthBarProps { health: 1. }));
+/// ```
+#[derive(Component, Clone)]
+pub struct SptsDioxusRootComponent {
+    /// Address of the component fn, the root is rebuilt when it changes.
+    component: usize,
+    props: Arc<dyn AnyRootProps>,
+}
+
+impl SptsDioxusRootComponent {
+    pub fn new(component: fn() -> Element) -> Self {
+        Self {
+            component: component as usize,
+            props: Arc::new(NoProps(component)),
+        }
+    }
+
+    /// Inserting it again with props that aren't equal to the current ones re-renders the root.
+    pub fn with_props<P: Clone + PartialEq + Send + Sync + 'static>(
+        component: fn(P) -> Element,
+        props: P,
+    ) -> Self {
+        Self {
+            component: component as usize,
+            props: Arc::new(WithProps { component, props }),
+        }
+    }
+
+    /// Whether `other` mounts the same component fn, the props may differ.
+    pub(crate) fn is_same_component(&self, other: &Self) -> bool {
+        self.component == other.component
+            && self.props.as_any().type_id() == other.props.as_any().type_id()
+    }
+
+    pub(crate) fn new_virtual_dom(&self) -> VirtualDom {
+        self.props.new_virtual_dom()
+    }
+
+    /// Passes the props to a `VirtualDom` created by `previous` (the same component) and
+    /// re-renders it, unless they're equal to `previous`'s props.
+    pub(crate) fn update_props(&self, previous: &Self, virtual_dom: &mut VirtualDom) {
+        self.props.update_props(previous.props.as_any(), virtual_dom);
+    }
+}
+
+impl std::fmt::Debug for SptsDioxusRootComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SptsDioxusRootComponent")
+            .field("component", &(self.component as *const ()))
+            .finish_non_exhaustive()
+    }
+}
+
+trait AnyRootProps: Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+    fn new_virtual_dom(&self) -> VirtualDom;
+    fn update_props(&self, previous: &dyn Any, virtual_dom: &mut VirtualDom);
+}
+
+struct NoProps(fn() -> Element);
+
+impl AnyRootProps for NoProps {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_virtual_dom(&self) -> VirtualDom {
+        VirtualDom::new(self.0)
+    }
+
+    fn update_props(&self, _previous: &dyn Any, _virtual_dom: &mut VirtualDom) {}
+}
+
+struct WithProps<P> {
+    component: fn(P) -> Element,
+    props: P,
+}
+
+/// The props are provided as a root context rather than the `VirtualDom`'s root props so they can
+/// be swapped out without rebuilding.
+#[derive(Clone)]
+struct RootProps<P>(P);
+
+fn props_root<P: Clone + 'static>(component: fn(P) -> Element) -> Element {
+    let RootProps(props) = consume_context::<RootProps<P>>();
+    component(props)
+}
+
+impl<P: Clone + PartialEq + Send + Sync + 'static> AnyRootProps for WithProps<P> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_virtual_dom(&self) -> VirtualDom {
+        let virtual_dom = VirtualDom::new_with_props(props_root::<P>, self.component);
+        virtual_dom.provide_root_context(RootProps(self.props.clone()));
+        virtual_dom
+    }
+
+    fn update_props(&self, previous: &dyn Any, virtual_dom: &mut VirtualDom) {
+        let unchanged = previous
+            .downcast_ref::<Self>()
+            .is_some_and(|previous| previous.props == self.props);
+        if unchanged {
+            return;
+        }
+        virtual_dom.provide_root_context(RootProps(self.props.clone()));
+        virtual_dom.mark_dirty(ScopeId::APP);
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use bevy_ecs::{component::Component, entity::Entity, system::Resource, world::World};
+use bevy_hierarchy::Children;
+use bevy_reflect::{PartialReflect, Reflect, ReflectRef, TypePath, VariantType};
+use dioxus::dioxus_core::{AttributeValue, ElementId};
+
+use crate::{
+    adapter::{SptsDioxusElementTag, SptsDioxusTemplateNode, SptsDioxusText},
+    SptsDioxusContext, SptsDioxusRoot,
+};
+
+/// Insert on an entity with a `SptsDioxusRootComponent` to keep the attributes applied to each
+/// element, so they're written to its snapshots.  Insert it along with the root component so the
+/// attributes of the first render are kept.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct SptsDioxusSnapshotAttributes;
+
+type SnapshotComponent =
+    Box<dyn Fn(&World, Entity) -> Option<(&'static str, String)> + Send + Sync>;
+
+/// Components that are written (through `Reflect`) to the snapshots of the elements that have
+/// them.
+///
+/// # Example
+///
+/// ```ignore
+/// app.world_mut()
+///     .resource_mut::<SptsDioxusSnapshotComponents>()
+///     .add::<Transform>()
+///     .add::<Visibility>();
+/// ```
+#[derive(Resource, Default)]
+pub struct SptsDioxusSnapshotComponents {
+    components: Vec<SnapshotComponent>,
+}
+
+impl SptsDioxusSnapshotComponents {
+    pub fn add<C: Component + Reflect + TypePath>(&mut self) -> &mut Self {
+        self.components.push(Box::new(|world, entity| {
+            let component = world.get::<C>(entity)?;
+            let mut value = String::new();
+            write_reflect(&mut value, component.as_partial_reflect()).ok()?;
+            Some((C::short_type_path(), value))
+        }));
+        self
+    }
+}
+
+/// Snapshot of the entities rendered by the root on `root_entity`, see
+/// [SptsDioxusRoot::snapshot].  `None` if there's no root on the entity or it's rendering.
+pub fn snapshot_root<TT: SptsDioxusTemplateNode>(
+    world: &World,
+    root_entity: Entity,
+) -> Option<String> {
+    let root = world
+        .get_non_send_resource::<SptsDioxusContext<TT>>()?
+        .roots
+        .get(&root_entity)?;
+    Some(root.snapshot(world))
+}
+
+impl<TT: SptsDioxusTemplateNode> SptsDioxusRoot<TT> {
+    /// RON like snapshot of the entities that the root rendered, with each element's tag,
+    /// `ElementId`, attributes (if the root has [SptsDioxusSnapshotAttributes]) and the
+    /// [SptsDioxusSnapshotComponents].  It doesn't contain
+    /// entity ids so snapshots are stable between runs and can be diffed between frames.
+    ///
+    /// Entities spawned below the root by other systems are left out.
+    pub fn snapshot(&self, world: &World) -> String {
+        let mut snapshot = String::new();
+        if let Some(root_entity) = self.el_to_entity.get(&ElementId(0)) {
+            let components = world.get_resource::<SptsDioxusSnapshotComponents>();
+            self.write_children(world, components, *root_entity, 0, &mut snapshot)
+                .expect("Writing to a String can't fail.");
+        }
+        snapshot
+    }
+
+    fn write_children(
+        &self,
+        world: &World,
+        components: Option<&SptsDioxusSnapshotComponents>,
+        entity: Entity,
+        depth: usize,
+        out: &mut String,
+    ) -> fmt::Result {
+        let Some(children) = world.get::<Children>(entity) else {
+            return Ok(());
+        };
+        for child in children.iter() {
+            let tag = world.get::<SptsDioxusElementTag>(*child);
+            let text = world.get::<SptsDioxusText>(*child);
+            let element_id = self.entity_to_el.get(child);
+            if tag.is_none() && text.is_none() && element_id.is_none() {
+                continue;
+            }
+
+            let indent = "  ".repeat(depth);
+            writeln!(out, "{indent}(")?;
+            match (tag, text) {
+                (Some(tag), _) => writeln!(out, "{indent}  tag: {:?},", tag.0)?,
+                (None, Some(text)) => writeln!(out, "{indent}  text: {:?},", text.0)?,
+                (None, None) => writeln!(out, "{indent}  placeholder: true,")?,
+            }
+            if let Some(element_id) = element_id {
+                writeln!(out, "{indent}  element: {},", element_id.0)?;
+            }
+
+            if let Some(attributes) = self.attributes.get(child) {
+                writeln!(out, "{indent}  attributes: {{")?;
+                for (name, value) in attributes {
+                    writeln!(
+                        out,
+                        "{indent}    {name:?}: {},",
+                        format_attribute_value(value)
+                    )?;
+                }
+                writeln!(out, "{indent}  }},")?;
+            }
+
+            let entity_components: BTreeMap<&str, String> = components
+                .into_iter()
+                .flat_map(|components| &components.components)
+                .filter_map(|component| component(world, *child))
+                .collect();
+            if !entity_components.is_empty() {
+                writeln!(out, "{indent}  components: {{")?;
+                for (name, value) in entity_components {
+                    writeln!(out, "{indent}    {name:?}: {value},")?;
+                }
+                writeln!(out, "{indent}  }},")?;
+            }
+
+            let mut children = String::new();
+            self.write_children(world, components, *child, depth + 2, &mut children)?;
+            if !children.is_empty() {
+                writeln!(out, "{indent}  children: [")?;
+                out.push_str(&children);
+                writeln!(out, "{indent}  ],")?;
+            }
+            writeln!(out, "{indent}),")?;
+        }
+        Ok(())
+    }
+}
+
+fn format_attribute_value(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::Text(text) => format!("{text:?}"),
+        AttributeValue::Float(float) => format!("{float:?}"),
+        AttributeValue::Int(int) => int.to_string(),
+        AttributeValue::Bool(bool) => bool.to_string(),
+        // Only the type is known, the components the attribute inserted show the value.
+        AttributeValue::Any(_) => "Any".to_string(),
+        AttributeValue::Listener(_) => "Listener".to_string(),
+        AttributeValue::None => "None".to_string(),
+    }
+}
+
+/// Writes `value` in RON like syntax with short type names.  Maps and sets are sorted so the
+/// output is stable.
+fn write_reflect(out: &mut String, value: &dyn PartialReflect) -> fmt::Result {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            write!(out, "{}(", value.reflect_short_type_path())?;
+            for i in 0..value.field_len() {
+                let separator = if i == 0 { "" } else { ", " };
+                write!(out, "{separator}{}: ", value.name_at(i).unwrap_or_default())?;
+                write_reflect(out, value.field_at(i).unwrap())?;
+            }
+            write!(out, ")")
+        }
+        ReflectRef::TupleStruct(value) => {
+            write!(out, "{}", value.reflect_short_type_path())?;
+            write_sequence(out, "(", ")", value.iter_fields())
+        }
+        ReflectRef::Tuple(value) => write_sequence(out, "(", ")", value.iter_fields()),
+        ReflectRef::List(value) => write_sequence(out, "[", "]", value.iter()),
+        ReflectRef::Array(value) => write_sequence(out, "[", "]", value.iter()),
+        ReflectRef::Set(value) => {
+            let items = value.iter().map(|item| {
+                let mut formatted = String::new();
+                write_reflect(&mut formatted, item).map(|_| formatted)
+            });
+            let mut items = items.collect::<Result<Vec<_>, _>>()?;
+            items.sort();
+            write!(out, "[{}]", items.join(", "))
+        }
+        ReflectRef::Map(value) => {
+            let mut entries = Vec::new();
+            for (key, entry) in value.iter() {
+                let mut formatted = String::new();
+                write_reflect(&mut formatted, key)?;
+                formatted.push_str(": ");
+                write_reflect(&mut formatted, entry)?;
+                entries.push(formatted);
+            }
+            entries.sort();
+            write!(out, "{{{}}}", entries.join(", "))
+        }
+        ReflectRef::Enum(value) => {
+            write!(out, "{}", value.variant_name())?;
+            match value.variant_type() {
+                VariantType::Unit => Ok(()),
+                VariantType::Tuple => {
+                    write_sequence(out, "(", ")", value.iter_fields().map(|f| f.value()))
+                }
+                VariantType::Struct => {
+                    write!(out, "(")?;
+                    for (i, field) in value.iter_fields().enumerate() {
+                        let separator = if i == 0 { "" } else { ", " };
+                        write!(out, "{separator}{}: ", field.name().unwrap_or_default())?;
+                        write_reflect(out, field.value())?;
+                    }
+                    write!(out, ")")
+                }
+            }
+        }
+        _ => write!(out, "{value:?}"),
+    }
+}
+
+fn write_sequence<'a>(
+    out: &mut String,
+    open: &str,
+    close: &str,
+    items: impl Iterator<Item = &'a dyn PartialReflect>,
+) -> fmt::Result {
+    write!(out, "{open}")?;
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            write!(out, ", ")?;
+        }
+        write_reflect(out, item)?;
+    }
+    write!(out, "{close}")
+}
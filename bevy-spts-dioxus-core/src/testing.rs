@@ -0,0 +1,197 @@
+use std::{fmt::Write, marker::PhantomData};
+
+use bevy_app::App;
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use bevy_hierarchy::Children;
+use dioxus::{prelude::Element, signals::Readable};
+
+use crate::{
+    adapter::{SptsDioxusElementTag, SptsDioxusTemplateNode, SptsDioxusText},
+    hooks::use_entity::EntitySignal,
+    snapshot::{snapshot_root, SptsDioxusSnapshotAttributes},
+    SptsDioxusPlugin, SptsDioxusRootComponent,
+};
+
+/// Headless `App` with just the [SptsDioxusPlugin] and one root, for testing rsx trees without a
+/// window or GPU.
+///
+/// # Example
+///
+/// ```ignore
+/// #[test]
+/// fn renders_a_button_per_item() {
+///     let mut test_app = SptsDioxusTestApp::<SptsDioxusAdapter>::new(Inventory);
+///     test_app.app_mut().insert_resource(Items(vec![Item::Sword, Item::Shield]));
+///     test_app.step();
+///     assert_eq!(test_app.find_by_tag("button").len(), 2);
+///     test_app.assert_hierarchy(
+///         "
+///         list
+///           button
+///             \"Sword\"
+///           button
+///             \"Shield\"
+///         ",
+///     );
+/// }
+/// ```
+pub struct SptsDioxusTestApp<TT: SptsDioxusTemplateNode> {
+    app: App,
+    root: Entity,
+    pd: PhantomData<TT>,
+}
+
+impl<TT: SptsDioxusTemplateNode> SptsDioxusTestApp<TT> {
+    /// Spawns `component` as a root, it's rendered on the first [SptsDioxusTestApp::step].
+    pub fn new(component: fn() -> Element) -> Self {
+        Self::with_root(SptsDioxusRootComponent::new(component))
+    }
+
+    /// The root keeps its attributes for [SptsDioxusTestApp::snapshot].
+    pub fn with_root(root_component: SptsDioxusRootComponent) -> Self {
+        let mut app = App::new();
+        app.add_plugins(SptsDioxusPlugin::<TT>::default());
+        let root = app
+            .world_mut()
+            .spawn((root_component, SptsDioxusSnapshotAttributes))
+            .id();
+        Self {
+            app,
+            root,
+            pd: PhantomData,
+        }
+    }
+
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// Use it to add plugins, systems and resources that the components depend on.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Entity that the root component is mounted on.
+    pub fn root(&self) -> Entity {
+        self.root
+    }
+
+    /// Runs one frame, rendering the root and applying its mutations.
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    pub fn step_frames(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    /// Entities below the root, depth first in hierarchy order.
+    pub fn descendants(&self) -> Vec<Entity> {
+        let world = self.world();
+        let mut descendants = Vec::new();
+        let mut stack = vec![self.root];
+        while let Some(entity) = stack.pop() {
+            if entity != self.root {
+                descendants.push(entity);
+            }
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.extend(children.iter().rev());
+            }
+        }
+        descendants
+    }
+
+    /// Elements spawned from `tag` (i.e. `"button"` for `button { }`) in hierarchy order.
+    pub fn find_by_tag(&self, tag: &str) -> Vec<Entity> {
+        self.find_by_component(|element_tag: &SptsDioxusElementTag| element_tag.0 == tag)
+    }
+
+    /// Entities below the root with a `C` that matches `predicate`, in hierarchy order.  Use it
+    /// to find elements by the components their attributes insert.
+    pub fn find_by_component<C: Component>(&self, predicate: impl Fn(&C) -> bool) -> Vec<Entity> {
+        let world = self.world();
+        self.descendants()
+            .into_iter()
+            .filter(|entity| world.get::<C>(*entity).is_some_and(&predicate))
+            .collect()
+    }
+
+    /// Entity that `signal` was passed to with the `entity` attribute, if it's mounted.
+    pub fn find_by_entity_signal(&self, signal: EntitySignal) -> Option<Entity> {
+        signal.try_peek().ok().and_then(|entity| *entity)
+    }
+
+    /// Element tag of `entity`, or the text of a text node.
+    pub fn describe(&self, entity: Entity) -> Option<String> {
+        let world = self.world();
+        if let Some(tag) = world.get::<SptsDioxusElementTag>(entity) {
+            return Some(tag.0.to_string());
+        }
+        world
+            .get::<SptsDioxusText>(entity)
+            .map(|text| format!("{:?}", text.0))
+    }
+
+    /// Tags (and text) of the entities below the root, indented by two spaces per level.
+    /// Entities that weren't spawned from an element or text node are skipped, their children
+    /// are kept.
+    pub fn hierarchy(&self) -> String {
+        let mut hierarchy = String::new();
+        self.write_hierarchy(self.root, 0, &mut hierarchy);
+        hierarchy
+    }
+
+    fn write_hierarchy(&self, entity: Entity, depth: usize, hierarchy: &mut String) {
+        let Some(children) = self.world().get::<Children>(entity) else {
+            return;
+        };
+        for child in children.iter() {
+            match self.describe(*child) {
+                Some(description) => {
+                    writeln!(hierarchy, "{}{description}", "  ".repeat(depth)).unwrap();
+                    self.write_hierarchy(*child, depth + 1, hierarchy);
+                }
+                None => self.write_hierarchy(*child, depth, hierarchy),
+            }
+        }
+    }
+
+    /// Snapshot of the root's entities, see [crate::SptsDioxusRoot::snapshot].
+    pub fn snapshot(&self) -> String {
+        snapshot_root::<TT>(self.world(), self.root).unwrap_or_default()
+    }
+
+    /// Panics if [SptsDioxusTestApp::hierarchy] doesn't match `expected`.  Blank lines and the
+    /// indentation that all lines share are ignored, so `expected` can be an indented string.
+    #[track_caller]
+    pub fn assert_hierarchy(&self, expected: &str) {
+        let lines: Vec<&str> = expected
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        let shared_indent = lines
+            .iter()
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let expected: String = lines
+            .iter()
+            .map(|line| format!("{}\n", line[shared_indent..].trim_end()))
+            .collect();
+        let actual = self.hierarchy();
+        assert!(
+            actual == expected,
+            "bevy_spts_dioxus: Hierarchy doesn't match.\nexpected:\n{expected}\nactual:\n{actual}"
+        );
+    }
+}
@@ -1,75 +1,189 @@
-use bevy_ecs::prelude::{Entity, World};
-use bevy_utils::HashMap;
+use bevy_ecs::world::Command;
+use bevy_ecs::{
+    entity::EntityHashMap,
+    prelude::{DetectChanges, Entity, Mut, Ref, World},
+};
+use bevy_hierarchy::{DespawnRecursive, Parent};
+use bevy_utils::tracing::{debug, debug_span};
+use dioxus::prelude::Event;
 
 use crate::{
-    adapter::SptsDioxusTemplateNode, deferred_system::DeferredSystemRunQueue,
-    ecs_hooks::EcsContext, mutations::MutationApplier, SptsDioxusContext, SptsDioxusRoot,
-    SptsDioxusRootComponent,
+    adapter::SptsDioxusTemplateNode,
+    deferred_system::DeferredSystemRunQueue,
+    events::{EventReaders, SptsDioxusEvent, SptsDioxusEventListeners},
+    mutation_log::SptsDioxusRecordMutations,
+    mutations::{MutationApplier, SptsDioxusMutationSummary},
+    snapshot::SptsDioxusSnapshotAttributes,
+    SptsDioxusContext, SptsDioxusRoot, SptsDioxusRootComponent,
 };
 
-pub fn tick_dioxus_ui<TT: SptsDioxusTemplateNode>(world: &mut World) {
-    run_deferred_systems(world);
-
-    // let ui_events = world.resource_scope(|world, mut event_readers: Mut<EventReaders>| {
-    //     event_readers.read_events(
-    //         world.resource(),
-    //         world.resource(),
-    //         world.resource(),
-    //         world.resource(),
-    //         world.resource(),
-    //         world.resource(),
-    //         world.resource(),
-    //     )
-    // });
-
-    let root_entities: HashMap<Entity, SptsDioxusRootComponent> = world
-        .query::<(Entity, &SptsDioxusRootComponent)>()
+/// Runs the systems that hooks queued with `use_system_schedule` and friends.
+pub fn run_deferred_systems(world: &mut World) {
+    DeferredSystemRunQueue::run_queued(world);
+}
+
+/// Tears down / creates roots, dispatches events to them and marks the scopes whose
+/// subscriptions changed as dirty.
+pub fn update_subscriptions<TT: SptsDioxusTemplateNode>(world: &mut World) {
+    let ui_events = world.resource_scope(|world, mut event_readers: Mut<EventReaders>| {
+        event_readers.read_events(world.resource())
+    });
+
+    // Whether the component changed is used to pass new props to the root.
+    let root_components: EntityHashMap<(SptsDioxusRootComponent, bool)> = world
+        .query::<(Entity, Ref<SptsDioxusRootComponent>)>()
         .iter(world)
-        .map(|(entity, root_component)| (entity, *root_component))
+        .map(|(entity, root_component)| {
+            (
+                entity,
+                (root_component.clone(), root_component.is_changed()),
+            )
+        })
         .collect();
     let mut roots =
         std::mem::take(&mut world.non_send_resource_mut::<SptsDioxusContext<TT>>().roots);
 
-    for (root_entity, dioxus_ui_root) in root_entities {
-        let mut root = roots
-            .remove(&(root_entity, dioxus_ui_root))
-            .unwrap_or_else(|| SptsDioxusRoot::new(dioxus_ui_root));
+    // Tear down roots whose entity was despawned or whose component was removed / swapped before
+    // rendering, so a swapped component (or a root whose entities were tampered with) is rebuilt
+    // from scratch.
+    let stale_roots: Vec<Entity> = roots
+        .iter()
+        .filter(|(root_entity, root)| {
+            root.needs_teardown
+                || !root_components.get(*root_entity).is_some_and(|(root_component, _)| {
+                    root.component.is_same_component(root_component)
+                })
+        })
+        .map(|(root_entity, _)| *root_entity)
+        .collect();
+    for root_entity in stale_roots {
+        let root = roots.remove(&root_entity).unwrap();
+        teardown_ui(root_entity, root, world);
+    }
+
+    for (root_entity, (root_component, changed)) in root_components {
+        let mut root = match roots.remove(&root_entity) {
+            Some(mut root) => {
+                if changed {
+                    root_component.update_props(&root.component, &mut root.virtual_dom);
+                    root.component = root_component;
+                }
+                root
+            }
+            None => SptsDioxusRoot::new(root_component),
+        };
 
-        // dispatch_ui_events(&ui_events, &mut ui_root, world);
+        dispatch_ui_events(&ui_events, &mut root, world);
 
         schedule_ui_renders_from_ecs_subscriptions(&mut root, world);
 
-        render_ui(root_entity, &mut root, world);
+        roots.insert(root_entity, root);
+    }
+    world
+        .non_send_resource_mut::<SptsDioxusContext<TT>>()
+        .roots
+        .extend(roots);
+}
+
+/// Renders the dirty scopes of each root, the mutations are applied by [apply_mutations].
+pub fn render_roots<TT: SptsDioxusTemplateNode>(world: &mut World) {
+    for_each_root::<TT>(world, render_ui);
+}
+
+/// Applies the mutations rendered by [render_roots] and the writes hooks deferred while rendering.
+pub fn apply_mutations<TT: SptsDioxusTemplateNode>(world: &mut World) {
+    for_each_root::<TT>(world, |root_entity, ui_root, world| {
+        apply_ui_mutations(root_entity, ui_root, world);
+        apply_world_writes(ui_root, world);
+    });
+}
 
-        world
-            .non_send_resource_mut::<SptsDioxusContext<TT>>()
-            .roots
-            .insert((root_entity, dioxus_ui_root), root);
+/// Takes the roots out of the context while `f` runs, as the hooks access the world.
+fn for_each_root<TT: SptsDioxusTemplateNode>(
+    world: &mut World,
+    mut f: impl FnMut(Entity, &mut SptsDioxusRoot<TT>, &mut World),
+) {
+    let mut roots =
+        std::mem::take(&mut world.non_send_resource_mut::<SptsDioxusContext<TT>>().roots);
+    for (root_entity, root) in roots.iter_mut() {
+        f(*root_entity, root, world);
     }
+    world
+        .non_send_resource_mut::<SptsDioxusContext<TT>>()
+        .roots
+        .extend(roots);
 }
 
-fn run_deferred_systems(world: &mut World) {
-    for mut system in std::mem::take(&mut *world.resource_mut::<DeferredSystemRunQueue>().run_queue)
-    {
-        system.initialize(world);
-        system.run((), world);
+fn dispatch_ui_events<TT: SptsDioxusTemplateNode>(
+    events: &[SptsDioxusEvent],
+    ui_root: &mut SptsDioxusRoot<TT>,
+    world: &mut World,
+) {
+    // Event targets are found up front as the handlers can access the world.
+    let mut targeted_events = Vec::new();
+    for event in events {
+        // Bubble up the bevy hierarchy until we find an entity managed by this root.  Dioxus only
+        // knows about elements with listeners, so bubbling events skip managed entities that
+        // aren't listening to this event.
+        let mut target = Some(event.target);
+        let mut target_element_id = None;
+        while let Some(entity) = target {
+            let entity_ref = world.get_entity(entity).ok();
+            target_element_id = ui_root.entity_to_el.get(&entity).copied();
+            let listening = entity_ref
+                .and_then(|entity_ref| entity_ref.get::<SptsDioxusEventListeners>())
+                .is_some_and(|listeners| listeners.has_listener(event.name));
+            if target_element_id.is_some() && (listening || !event.bubbles) {
+                break;
+            }
+            target_element_id = None;
+            target = entity_ref
+                .and_then(|entity_ref| entity_ref.get::<Parent>())
+                .map(Parent::get);
+        }
+
+        if let Some(target_element_id) = target_element_id {
+            targeted_events.push((event, target_element_id));
+        }
     }
+
+    let virtual_dom = &ui_root.virtual_dom;
+    ui_root.world_scope.scope(world, || {
+        for (event, target_element_id) in targeted_events {
+            virtual_dom.runtime().handle_event(
+                event.name,
+                Event::new(event.data(), event.bubbles),
+                target_element_id,
+            );
+        }
+    });
 }
 
 fn schedule_ui_renders_from_ecs_subscriptions<TT: SptsDioxusTemplateNode>(
     ui_root: &mut SptsDioxusRoot<TT>,
     world: &World,
 ) {
-    let ecs_subscriptions = &world
-        .non_send_resource::<SptsDioxusContext<TT>>()
-        .subscriptions;
+    let ecs_subscriptions = &mut *ui_root.subscriptions.borrow_mut();
 
-    for scope_id in &*ecs_subscriptions.world_and_queries {
+    for scope_id in &ecs_subscriptions.world {
         ui_root.virtual_dom.mark_dirty(*scope_id);
     }
 
-    for (resource_id, scope_ids) in &*ecs_subscriptions.resources {
-        if world.is_resource_changed_by_id(*resource_id) {
+    for (scope_id, changed) in ecs_subscriptions.changes.values_mut() {
+        if changed(world) {
+            ui_root.virtual_dom.mark_dirty(*scope_id);
+        }
+    }
+
+    // Checked against the last check rather than this system's last run, so writes applied after
+    // the last render (see `apply_world_writes`) count as changes.
+    let this_run = world.read_change_tick();
+    let last_run = std::mem::replace(&mut ecs_subscriptions.last_run, this_run);
+    for (resource_id, scope_ids) in &ecs_subscriptions.resources {
+        let resource_changed = world
+            .get_resource_change_ticks_by_id(*resource_id)
+            .is_some_and(|ticks| ticks.is_changed(last_run, this_run));
+        if resource_changed {
             for scope_id in scope_ids {
                 ui_root.virtual_dom.mark_dirty(*scope_id);
             }
@@ -90,23 +204,114 @@ fn render_ui<TT: SptsDioxusTemplateNode>(
     ui_root: &mut SptsDioxusRoot<TT>,
     world: &mut World,
 ) {
-    ui_root
-        .virtual_dom
-        .provide_root_context(EcsContext::<TT>::new(world));
-
-    #[cfg(feature = "hot_reload")]
-    crate::hot_reload::update_templates(world, &mut ui_root.virtual_dom);
-
-    let mut mutation_applier = MutationApplier::<TT>::new(
-        &mut ui_root.el_to_entity,
-        &mut ui_root.entity_to_el,
-        &mut ui_root.entity_refs,
-        root_entity,
-        world,
-    );
-    if ui_root.needs_rebuild {
-        ui_root.virtual_dom.rebuild(&mut mutation_applier);
-        ui_root.needs_rebuild = false;
+    let _span = debug_span!("bevy_spts_dioxus::render", root = ?root_entity).entered();
+
+    let SptsDioxusRoot {
+        virtual_dom,
+        world_scope,
+        pending_mutations,
+        needs_rebuild,
+        ..
+    } = ui_root;
+    world_scope.scope(world, || {
+        if *needs_rebuild {
+            virtual_dom.rebuild(pending_mutations);
+            *needs_rebuild = false;
+        }
+        virtual_dom.render_immediate(pending_mutations);
+    });
+}
+
+fn apply_ui_mutations<TT: SptsDioxusTemplateNode>(
+    root_entity: Entity,
+    ui_root: &mut SptsDioxusRoot<TT>,
+    world: &mut World,
+) {
+    let _span = debug_span!("bevy_spts_dioxus::apply_mutations", root = ?root_entity).entered();
+
+    let mutations = std::mem::take(&mut ui_root.pending_mutations);
+    if mutations.mutations.is_empty() {
+        return;
+    }
+    if world.get::<SptsDioxusRecordMutations>(root_entity).is_some() {
+        ui_root
+            .mutation_log
+            .get_or_insert_with(Default::default)
+            .append(&mutations);
+    }
+
+    let attributes = if world.get::<SptsDioxusSnapshotAttributes>(root_entity).is_some() {
+        Some(&mut ui_root.attributes)
+    } else {
+        ui_root.attributes.clear();
+        None
+    };
+    // Setting the `entity` attributes' signals needs the dioxus runtime.
+    let summary = ui_root.virtual_dom.in_runtime(|| {
+        let mut mutation_applier = MutationApplier::<TT>::new(
+            &mut ui_root.el_to_entity,
+            &mut ui_root.entity_to_el,
+            &mut ui_root.entity_refs,
+            &mut ui_root.observers,
+            attributes,
+            &mut ui_root.templates,
+            root_entity,
+            world,
+        );
+        mutations.apply_to(&mut mutation_applier);
+        mutation_applier.summary()
+    });
+    if summary.is_empty() {
+        return;
+    }
+    if let Some(mut root_summary) = world.get_mut::<SptsDioxusMutationSummary>(root_entity) {
+        *root_summary = summary;
+        debug!(
+            created = summary.created,
+            removed = summary.removed,
+            updated = summary.updated,
+            "Applied mutations"
+        );
+    }
+}
+
+fn apply_world_writes<TT: SptsDioxusTemplateNode>(ui_root: &SptsDioxusRoot<TT>, world: &mut World) {
+    if ui_root.world_writes.is_empty() {
+        return;
+    }
+    // Otherwise the writes share the change tick that the subscriptions were last checked at and
+    // wouldn't be seen as changes on the next run.
+    world.increment_change_tick();
+    ui_root.world_writes.apply(world);
+}
+
+/// Drops the root's `VirtualDom` (running `use_drop` hooks, which remove its subscriptions) and
+/// despawns the entities it spawned.
+fn teardown_ui<TT: SptsDioxusTemplateNode>(
+    root_entity: Entity,
+    ui_root: SptsDioxusRoot<TT>,
+    world: &mut World,
+) {
+    let _span = debug_span!("bevy_spts_dioxus::teardown", root = ?root_entity).entered();
+
+    let SptsDioxusRoot {
+        virtual_dom,
+        entity_to_el,
+        ..
+    } = ui_root;
+    drop(virtual_dom);
+
+    let mut despawned = 0;
+    for entity in entity_to_el.into_keys() {
+        // Managed entities may already be gone with the root or one of their ancestors.
+        if entity != root_entity && world.get_entity(entity).is_ok() {
+            DespawnRecursive {
+                warn: false,
+                entity,
+            }
+            .apply(world);
+            despawned += 1;
+        }
     }
-    ui_root.virtual_dom.render_immediate(&mut mutation_applier);
+    debug!(despawned, "Tore down root");
 }
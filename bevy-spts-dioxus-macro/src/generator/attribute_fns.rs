@@ -7,7 +7,7 @@ pub fn generate_attribute_fns(model: &Model) -> TokenStream {
     let attr_implementations: TokenStream = model
         .attribute_definitions
         .values()
-        .map(|attr_def| attr_def.to_token_stream())
+        .map(|attr_def| attr_def.item_fn.to_token_stream())
         .collect();
 
     quote! {
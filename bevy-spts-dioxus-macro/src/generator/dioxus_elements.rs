@@ -1,7 +1,7 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
 
-use crate::parser::Model;
+use crate::parser::{element_definition::BUILT_IN_EVENTS, Model};
 
 // TODO: TAG_NAME must be moved into a submodule called "elements" with an impl the same name.
 pub fn generate_dioxus_elements_tag_names(model: &Model) -> TokenStream {
@@ -24,6 +24,107 @@ pub fn generate_dioxus_elements_tag_names(model: &Model) -> TokenStream {
     }
 }
 
+/// Pointer events that every element has with the `bevy_picking` feature, forwarded from
+/// `bevy_picking` like `#[event]` fields.
+#[cfg(feature = "bevy_picking")]
+const BUILT_IN_POINTER_EVENTS: [(&str, &str); 13] = [
+    ("onclick", "Click"),
+    ("onpointerdown", "Down"),
+    ("onpointerup", "Up"),
+    ("onpointermove", "Move"),
+    ("onpointerover", "Over"),
+    ("onpointerout", "Out"),
+    ("ondragstart", "DragStart"),
+    ("ondrag", "Drag"),
+    ("ondragend", "DragEnd"),
+    ("ondragenter", "DragEnter"),
+    ("ondragover", "DragOver"),
+    ("ondragleave", "DragLeave"),
+    ("ondrop", "DragDrop"),
+];
+
+#[cfg(not(feature = "bevy_picking"))]
+const BUILT_IN_POINTER_EVENTS: [(&str, &str); 0] = [];
+
+/// The built-in pointer events that no element overrides, as `(field_ident, event_type)`.
+pub fn built_in_pointer_events(model: &Model) -> Vec<(Ident, TokenStream)> {
+    let unique_events = model.dioxus_elements_module.unique_events();
+    BUILT_IN_POINTER_EVENTS
+        .iter()
+        .filter(|(name, _)| !unique_events.iter().any(|el_event| el_event.field_ident == name))
+        .map(|(name, event)| {
+            let event = Ident::new(event, Span::call_site());
+            (
+                Ident::new(name, Span::call_site()),
+                quote! {
+                    bevy_spts_dioxus::bevy_picking::events::Pointer<
+                        bevy_spts_dioxus::bevy_picking::events::#event
+                    >
+                },
+            )
+        })
+        .collect()
+}
+
+fn generate_event_listener(field_ident: &Ident, event_type: &TokenStream) -> TokenStream {
+    quote! {
+        pub fn #field_ident<__Marker>(
+            handler: impl dioxus_core::prelude::SuperInto<dioxus_core::prelude::EventHandler<dioxus_core::Event<#event_type>>, __Marker>,
+        ) -> dioxus_core::Attribute {
+            bevy_spts_dioxus::element_event_listener(stringify!(#field_ident), handler)
+        }
+
+        #[doc(hidden)]
+        pub mod #field_ident {
+            use super::*;
+
+            // rsx uses this version when it sees an inline closure to give better type inference.
+            pub fn call_with_explicit_closure<
+                __Marker,
+                Return: dioxus_core::SpawnIfAsync<__Marker> + 'static,
+            >(
+                handler: impl FnMut(dioxus_core::Event<#event_type>) -> Return + 'static,
+            ) -> dioxus_core::Attribute {
+                super::#field_ident(handler)
+            }
+        }
+    }
+}
+
+/// Generates the `dioxus_elements::events` module that rsx uses to type check event listeners,
+/// i.e. `onpress: move |e| { ... }`.
+pub fn generate_dioxus_elements_events(model: &Model) -> TokenStream {
+    let unique_events = model.dioxus_elements_module.unique_events();
+    let mut events: TokenStream = unique_events
+        .iter()
+        .map(|el_event| {
+            generate_event_listener(&el_event.field_ident, &el_event.event_type.to_token_stream())
+        })
+        .collect();
+
+    // Elements can't declare the built-in events, the parser rejects them.
+    for (name, event_type) in BUILT_IN_EVENTS {
+        let field_ident = Ident::new(name, Span::call_site());
+        let event_type = Ident::new(event_type, Span::call_site());
+        events.extend(generate_event_listener(
+            &field_ident,
+            &quote! { bevy_spts_dioxus::#event_type },
+        ));
+    }
+    for (field_ident, event_type) in built_in_pointer_events(model) {
+        events.extend(generate_event_listener(&field_ident, &event_type));
+    }
+
+    quote! {
+        pub mod events {
+            #[allow(unused_imports)]
+            use super::super::*;
+
+            #events
+        }
+    }
+}
+
 pub fn generate_dioxus_elements(model: &Model) -> TokenStream {
     let elements: TokenStream = model.dioxus_elements_module.element_definitions.iter().map(|el_defininition| {
         let element_attributes: TokenStream = el_defininition.attributes.iter().map(|el_attribute| {
@@ -45,6 +146,8 @@ pub fn generate_dioxus_elements(model: &Model) -> TokenStream {
                 pub const NAME_SPACE: Option<&'static str> = NAME_SPACE;
 
                 pub const entity: AttributeDescription = ("entity", None, false);
+                pub const observe: AttributeDescription = ("observe", None, false);
+                pub const tabindex: AttributeDescription = ("tabindex", None, false);
 
                 #element_attributes
                 #component_attributes
@@ -60,6 +163,7 @@ pub fn generate_dioxus_elements(model: &Model) -> TokenStream {
         .collect();
 
     let element_tag_names = generate_dioxus_elements_tag_names(model);
+    let element_events = generate_dioxus_elements_events(model);
 
     quote! {
         pub mod dioxus_elements {
@@ -70,6 +174,8 @@ pub fn generate_dioxus_elements(model: &Model) -> TokenStream {
 
             #element_tag_names
 
+            #element_events
+
             #elements
         }
     }
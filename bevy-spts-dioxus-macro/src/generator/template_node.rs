@@ -1,11 +1,10 @@
-use std::collections::HashSet;
-
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
-use syn::PathArguments;
+use syn::{PathArguments, TypePath};
 
+use crate::generator::dioxus_elements::built_in_pointer_events;
 use crate::parser::{
-    element_definition::{ElementAttribute, ElementComponent},
+    element_definition::{ComponentRemoveBehaviour, ElementAttribute, ElementComponent},
     Model,
 };
 
@@ -32,6 +31,7 @@ pub fn generate_template_node(model: &Model) -> TokenStream {
         pub enum SptsDioxusAdapter {
             #variants
 
+            Text { text: &'static str },
             Dynamic { id: usize },
         }
 
@@ -53,12 +53,15 @@ fn implement_from_dioxus(model: &Model) -> TokenStream {
                     attrs,
                     children,
                 } => {
-                    let children = children.iter().map(Self::from_dioxus).collect();
+                    let children = children
+                        .iter()
+                        .map(Self::try_from_dioxus)
+                        .collect::<Result<_, _>>()?;
                     let attributes: Vec<bevy_spts_dioxus::StaticTemplateAttribute> = attrs.
                         iter()
                         .filter_map(|v| v.try_into().ok())
                         .collect();
-                    Self::#element_ident { children, attributes }
+                    Ok(Self::#element_ident { children, attributes })
                 }
             }
         })
@@ -66,30 +69,55 @@ fn implement_from_dioxus(model: &Model) -> TokenStream {
 
     quote! {
         fn from_dioxus(node: &dioxus_core::TemplateNode) -> Self {
+            Self::try_from_dioxus(node).unwrap_or_else(|err| panic!("bevy_spts_dioxus: {err}"))
+        }
+
+        fn try_from_dioxus(node: &dioxus_core::TemplateNode) -> Result<Self, bevy_spts_dioxus::SptsDioxusErrorKind> {
             match node {
                 #defined_element_matches
 
+                dioxus_core::TemplateNode::Text { text } => {
+                    Ok(Self::Text { text })
+                }
+
+                // Dynamic text is created later via `SptsDioxusTemplateNode::spawn_text`.
                 dioxus_core::TemplateNode::Dynamic { id } => {
-                    Self::Dynamic { id: *id }
+                    Ok(Self::Dynamic { id: *id })
                 }
 
                 dioxus_core::TemplateNode::Element {
                     tag,
                     namespace,
-                    attrs,
-                    children,
+                    ..
                 } => {
-                    core::panic!("bevy_spts_dioxus: Unknown dioxus element '{tag}' with namespace {namespace:?}.")
-                }
-
-                other => {
-                    core::panic!("bevy_spts_dioxus: Unsupported dioxus node {other:?}.")
+                    Err(bevy_spts_dioxus::SptsDioxusErrorKind::UnknownElement { tag: *tag, namespace: *namespace })
                 }
             }
         }
     }
 }
 
+/// Generates the default value that a component is spawned with, e.g. `Handle::<Mesh>::default()`.
+fn component_default(component_type: &TypePath) -> TokenStream {
+    let last_segment = component_type.path.segments.last().unwrap();
+    // The identifier for the type, like `Handle`
+    let ident = &last_segment.ident;
+
+    // Check if there are any generic arguments, like `<Mesh>`
+    let ufc_generic_args = if let PathArguments::AngleBracketed(args) = &last_segment.arguments {
+        let args = &args.args;
+        quote! {::<#args>}
+    } else {
+        TokenStream::new()
+    };
+
+    let mut joined = ident.to_token_stream();
+    joined.extend(ufc_generic_args);
+
+    // Generate the final UFC syntax, e.g., `Handle::<Mesh>`
+    quote! { #joined::default() }
+}
+
 fn implement_spawn(model: &Model) -> TokenStream {
     let variant_matches: TokenStream = model
         .dioxus_elements_module
@@ -98,58 +126,73 @@ fn implement_spawn(model: &Model) -> TokenStream {
         .map(|el_def| {
             let element_ident = &el_def.ident;
 
-            let insert_components: TokenStream = if !el_def.components.is_empty() {
-                let component_defaults: TokenStream = el_def
-                    .components
-                    .iter()
-                    .map(|component_ident| {
-                        let ElementComponent { component_type, .. } = component_ident;
-
-                        let last_segment = component_type.path.segments.last().unwrap();
-                        // The identifier for the type, like `Handle`
-                        let ident = &last_segment.ident;
-
-                        // Check if there are any generic arguments, like `<Mesh>`
-                        let ufc_generic_args =
-                            if let PathArguments::AngleBracketed(args) = &last_segment.arguments {
-                                let args = &args.args;
-                                quote! {::<#args>}
-                            } else {
-                                TokenStream::new()
-                            };
-
-                        let mut joined = ident.to_token_stream();
-                        joined.extend(ufc_generic_args);
-
-                        // Generate the final UFC syntax, e.g., `Handle::<Mesh>`
-                        quote! {
-                            #joined::default(),
+            // Components inserted by `SptsDioxusElement::spawn` are kept and recorded so they can
+            // be reset to that value later.
+            let insert_components: TokenStream = el_def
+                .components
+                .iter()
+                .map(|el_component| {
+                    let component_type = &el_component.component_type;
+                    let component_default = component_default(component_type);
+                    quote! {
+                        if !entity_mut.contains::<#component_type>() {
+                            entity_mut.insert(#component_default);
                         }
-                    })
-                    .collect();
-                quote! { entity_mut.insert((#component_defaults)); }
-            } else {
-                TokenStream::new()
-            };
+                    }
+                })
+                .collect();
+            let record_components: TokenStream = el_def
+                .components
+                .iter()
+                .filter(|el_component| el_component.remove_behaviour == ComponentRemoveBehaviour::Reset)
+                .map(|el_component| {
+                    let ElementComponent { field_ident, component_type, .. } = el_component;
+                    quote! {
+                        bevy_spts_dioxus::record_spawned_component::<#component_type>(
+                            world,
+                            entity,
+                            stringify!(#element_ident),
+                            stringify!(#field_ident),
+                        );
+                    }
+                })
+                .collect();
 
             quote! {
                 Self::#element_ident { children, attributes } => {
-                    let children = children
-                        .iter()
-                        .map(|child| child.spawn(world))
-                        .collect::<Box<[_]>>();
+                    // The element is spawned after its children but comes before them in `nodes`.
+                    let index = nodes.len();
+                    nodes.push(Entity::PLACEHOLDER);
+                    // Keeps the first error, the element is despawned if anything failed.
+                    let mut result = Ok(());
+                    let mut spawned_children = Vec::with_capacity(children.len());
+                    for child in children.iter() {
+                        match child.try_spawn_nodes(world, nodes) {
+                            Ok(child) => spawned_children.push(child),
+                            Err(err) => result = result.and(Err(err)),
+                        }
+                    }
 
                     use bevy_spts_dioxus::SptsDioxusElement;
                     let mut entity_mut = dioxus_elements::#element_ident::spawn(world);
+                    entity_mut.insert(bevy_spts_dioxus::SptsDioxusElementTag(stringify!(#element_ident)));
                     #insert_components
-                    entity_mut.add_children(&children);
+                    entity_mut.add_children(&spawned_children);
                     let entity = entity_mut.id();
+                    nodes[index] = entity;
+                    #record_components
                     // Apply static attributes
                     for attr in attributes {
                         let value = dioxus_core::AttributeValue::Text(attr.value.into());
-                        Self::apply_attribute(world, entity, attr.name, &value);
+                        result = result.and(Self::try_apply_attribute(world, entity, attr.name, &value));
+                    }
+                    match result {
+                        Ok(()) => Ok(entity),
+                        Err(err) => {
+                            world.entity_mut(entity).despawn_recursive();
+                            Err(err)
+                        }
                     }
-                    entity
                 }
             }
         })
@@ -157,15 +200,32 @@ fn implement_spawn(model: &Model) -> TokenStream {
 
     quote! {
         fn spawn(&self, world: &mut World) -> Entity {
+            self.try_spawn(world).unwrap_or_else(|err| panic!("bevy_spts_dioxus: {err}"))
+        }
+
+        fn try_spawn(&self, world: &mut World) -> Result<Entity, bevy_spts_dioxus::SptsDioxusErrorKind> {
+            self.try_spawn_nodes(world, &mut Vec::new())
+        }
+
+        fn try_spawn_nodes(
+            &self,
+            world: &mut World,
+            nodes: &mut Vec<Entity>,
+        ) -> Result<Entity, bevy_spts_dioxus::SptsDioxusErrorKind> {
             match self {
                 #variant_matches
 
-                Self::Dynamic { id } => {
-                    world.spawn((
-                        Name::from("Dynamic"),
-                        Transform::default(),
-                        Visibility::default(),
-                    )).id()
+                Self::Text { text } => {
+                    let entity = Self::spawn_text(world, text);
+                    nodes.push(entity);
+                    Ok(entity)
+                }
+
+                // Replaced by the dynamic node once it's created.
+                Self::Dynamic { .. } => {
+                    let entity = Self::spawn_placeholder(world);
+                    nodes.push(entity);
+                    Ok(entity)
                 }
             }
         }
@@ -173,68 +233,272 @@ fn implement_spawn(model: &Model) -> TokenStream {
 }
 
 fn implement_apply_attribute(model: &Model) -> TokenStream {
-    let all_attributes: HashSet<&ElementAttribute> = model
+    let element_matches: TokenStream = model
         .dioxus_elements_module
         .element_definitions
         .iter()
-        .flat_map(|el_def| &el_def.attributes)
-        .collect();
-    let attribute_matches: TokenStream = all_attributes
-        .into_iter()
-        .map(|el_attribute| {
-            let ElementAttribute {
-                field_ident,
-                handler_ident,
-            } = el_attribute;
-            quote! { stringify!(#field_ident) => #handler_ident(world, entity, value), }
+        .map(|el_def| {
+            let element_ident = &el_def.ident;
+
+            let attribute_matches: TokenStream = el_def
+                .attributes
+                .iter()
+                .map(|el_attribute| {
+                    let ElementAttribute {
+                        field_ident,
+                        handler_ident,
+                    } = el_attribute;
+                    quote! { stringify!(#field_ident) => {
+                        #handler_ident(world, entity, value);
+                        Ok(())
+                    } }
+                })
+                .collect();
+
+            let component_matches: TokenStream = el_def
+                .components
+                .iter()
+                .map(|el_component| {
+                    let ElementComponent {
+                        field_ident,
+                        component_type,
+                        remove_behaviour,
+                    } = el_component;
+                    let set_value = match remove_behaviour {
+                        ComponentRemoveBehaviour::Reset => quote! {
+                            let mut current_value = entity_mut
+                                .get_mut::<#component_type>()
+                                .ok_or(bevy_spts_dioxus::SptsDioxusErrorKind::MissingComponent {
+                                    entity,
+                                    component: core::any::type_name::<#component_type>(),
+                                })?;
+                            *current_value = value;
+                        },
+                        // The component may have been removed along with the attribute.
+                        ComponentRemoveBehaviour::Remove => quote! {
+                            entity_mut.insert(value);
+                        },
+                    };
+                    quote! { stringify!(#field_ident) => {
+                        let value = value
+                            .as_concrete::<#component_type>()
+                            .ok_or(bevy_spts_dioxus::SptsDioxusErrorKind::InvalidAttributeValue {
+                                name,
+                                expected: core::any::type_name::<#component_type>(),
+                            })?
+                            .clone();
+                        let mut entity_mut = world.entity_mut(entity);
+                        #set_value
+                        Ok(())
+                    } }
+                })
+                .collect();
+
+            quote! {
+                stringify!(#element_ident) => match name {
+                    #attribute_matches
+                    #component_matches
+
+                    unknown => Err(bevy_spts_dioxus::SptsDioxusErrorKind::UnknownAttribute { tag, name: unknown }),
+                },
+            }
         })
         .collect();
 
-    let all_components: HashSet<&ElementComponent> = model
+    quote! {
+        fn apply_attribute(
+            world: &mut World,
+            entity: Entity,
+            name: &'static str,
+            value: &dioxus_core::AttributeValue,
+        ) {
+            Self::try_apply_attribute(world, entity, name, value)
+                .unwrap_or_else(|err| panic!("bevy_spts_dioxus: {err}"))
+        }
+
+        fn try_apply_attribute(
+            world: &mut World,
+            entity: Entity,
+            name: &'static str,
+            value: &dioxus_core::AttributeValue,
+        ) -> Result<(), bevy_spts_dioxus::SptsDioxusErrorKind> {
+            bevy_spts_dioxus::tracing::trace!(?entity, attribute = name, ?value, "apply_attribute");
+            // Attributes are namespaced by element so different elements can use the same key.
+            let tag = world
+                .get::<bevy_spts_dioxus::SptsDioxusElementTag>(entity)
+                .map(|tag| tag.0)
+                .ok_or(bevy_spts_dioxus::SptsDioxusErrorKind::MissingComponent {
+                    entity,
+                    component: "SptsDioxusElementTag",
+                })?;
+            match tag {
+                #element_matches
+
+                unknown => Err(bevy_spts_dioxus::SptsDioxusErrorKind::UnknownElement { tag: unknown, namespace: None }),
+            }
+        }
+    }
+}
+
+fn implement_remove_attribute(model: &Model) -> TokenStream {
+    let element_matches: TokenStream = model
         .dioxus_elements_module
         .element_definitions
         .iter()
-        .flat_map(|el_def| &el_def.components)
-        .collect();
-    let component_matches: TokenStream = all_components
-        .into_iter()
-        .map(|el_attribute| {
-            let ElementComponent {
-                field_ident,
-                component_type,
-            } = el_attribute;
-            quote! { stringify!(#field_ident) => {
-                let value = value
-                    .as_concrete::<#component_type>()
-                    .unwrap_or_else(|| panic!("bevy_spts_dioxus: While applying component attr '{}', couldn't downcast to type '{}'.", stringify!(#field_ident), stringify!(#component_type)))
-                    .clone();
-                let mut entity_mut = world.entity_mut(entity);
-                let mut current_value = entity_mut
-                    .get_mut::<#component_type>()
-                    .unwrap_or_else(|| panic!("bevy_spts_dioxus: While applying component attr '{}', couldn't get component '{}' on entity '{entity:?}' to mutate.", stringify!(#field_ident), stringify!(#component_type)));
-                *current_value = value;
-            } }
+        .map(|el_def| {
+            let element_ident = &el_def.ident;
+
+            let attribute_matches: TokenStream = el_def
+                .attributes
+                .iter()
+                .map(|el_attribute| {
+                    let ElementAttribute {
+                        field_ident,
+                        handler_ident,
+                    } = el_attribute;
+                    let remove_handler = model
+                        .attribute_definitions
+                        .get(&handler_ident.to_string())
+                        .and_then(|attr_def| attr_def.remove_handler.as_ref());
+                    match remove_handler {
+                        Some(remove_handler) => quote! { stringify!(#field_ident) => {
+                            #remove_handler(world, entity);
+                            Ok(())
+                        } },
+                        None => quote! { stringify!(#field_ident) => {
+                            #handler_ident(world, entity, &dioxus_core::AttributeValue::None);
+                            Ok(())
+                        } },
+                    }
+                })
+                .collect();
+
+            let component_matches: TokenStream = el_def
+                .components
+                .iter()
+                .map(|el_component| {
+                    let ElementComponent {
+                        field_ident,
+                        component_type,
+                        remove_behaviour,
+                    } = el_component;
+                    match remove_behaviour {
+                        ComponentRemoveBehaviour::Reset => quote! { stringify!(#field_ident) => {
+                            let value = bevy_spts_dioxus::spawned_component::<#component_type>(
+                                world,
+                                stringify!(#element_ident),
+                                stringify!(#field_ident),
+                            );
+                            world.entity_mut(entity).insert(value);
+                            Ok(())
+                        } },
+                        ComponentRemoveBehaviour::Remove => quote! { stringify!(#field_ident) => {
+                            world.entity_mut(entity).remove::<#component_type>();
+                            Ok(())
+                        } },
+                    }
+                })
+                .collect();
+
+            quote! {
+                stringify!(#element_ident) => match name {
+                    #attribute_matches
+                    #component_matches
+
+                    unknown => Err(bevy_spts_dioxus::SptsDioxusErrorKind::UnknownAttribute { tag, name: unknown }),
+                },
+            }
         })
         .collect();
 
     quote! {
-        fn apply_attribute(
+        fn remove_attribute(
             world: &mut World,
             entity: Entity,
             name: &'static str,
-            value: &dioxus_core::AttributeValue,
         ) {
-            println!("Applying attribute {name} with {value:?}.");
-            match name {
-                #attribute_matches
-                #component_matches
+            Self::try_remove_attribute(world, entity, name)
+                .unwrap_or_else(|err| panic!("bevy_spts_dioxus: {err}"))
+        }
+
+        fn try_remove_attribute(
+            world: &mut World,
+            entity: Entity,
+            name: &'static str,
+        ) -> Result<(), bevy_spts_dioxus::SptsDioxusErrorKind> {
+            bevy_spts_dioxus::tracing::trace!(?entity, attribute = name, "remove_attribute");
+            let tag = world
+                .get::<bevy_spts_dioxus::SptsDioxusElementTag>(entity)
+                .map(|tag| tag.0)
+                .ok_or(bevy_spts_dioxus::SptsDioxusErrorKind::MissingComponent {
+                    entity,
+                    component: "SptsDioxusElementTag",
+                })?;
+            match tag {
+                #element_matches
 
-                unknown => core::panic!("bevy_spts_dioxus: Unexpected attribute '{unknown}'."),
+                unknown => Err(bevy_spts_dioxus::SptsDioxusErrorKind::UnknownElement { tag: unknown, namespace: None }),
             }
         }
     }
 }
 
+fn implement_register_events(model: &Model) -> TokenStream {
+    let register_events: TokenStream = model
+        .dioxus_elements_module
+        .unique_events()
+        .into_iter()
+        .map(|el_event| {
+            let event_type = &el_event.event_type;
+            let event_name = el_event.event_name();
+            quote! { bevy_spts_dioxus::register_element_event::<#event_type>(app, #event_name); }
+        })
+        .collect();
+    let register_built_in_events: TokenStream = built_in_pointer_events(model)
+        .into_iter()
+        .map(|(field_ident, event_type)| {
+            let event_name = field_ident.to_string()[2..].to_string();
+            quote! { bevy_spts_dioxus::register_element_event::<#event_type>(app, #event_name); }
+        })
+        .collect();
+
+    quote! {
+        #[allow(unused_variables)]
+        fn register_events(app: &mut App) {
+            #register_events
+            #register_built_in_events
+        }
+    }
+}
+
+fn implement_text(model: &Model) -> TokenStream {
+    let Some(text_component) = &model.options.text_component else {
+        return TokenStream::new();
+    };
+
+    quote! {
+        fn spawn_text(world: &mut World, text: &str) -> Entity {
+            world.spawn(<#text_component>::from(text.to_string())).id()
+        }
+
+        fn set_text(world: &mut World, entity: Entity, text: &str) {
+            world.entity_mut(entity).insert(<#text_component>::from(text.to_string()));
+        }
+    }
+}
+
+fn implement_placeholder(model: &Model) -> TokenStream {
+    let Some(placeholder_fn) = &model.options.placeholder_fn else {
+        return TokenStream::new();
+    };
+
+    quote! {
+        fn spawn_placeholder(world: &mut World) -> Entity {
+            #placeholder_fn(world)
+        }
+    }
+}
+
 pub fn implement_template_node(model: &Model) -> TokenStream {
     let from_dioxus = implement_from_dioxus(model);
 
@@ -242,6 +506,14 @@ pub fn implement_template_node(model: &Model) -> TokenStream {
 
     let apply_attribute = implement_apply_attribute(model);
 
+    let remove_attribute = implement_remove_attribute(model);
+
+    let register_events = implement_register_events(model);
+
+    let text = implement_text(model);
+
+    let placeholder = implement_placeholder(model);
+
     quote! {
         impl bevy_spts_dioxus::SptsDioxusTemplateNode for SptsDioxusAdapter {
             #from_dioxus
@@ -249,6 +521,14 @@ pub fn implement_template_node(model: &Model) -> TokenStream {
             #spawn
 
             #apply_attribute
+
+            #remove_attribute
+
+            #register_events
+
+            #text
+
+            #placeholder
         }
     }
 }
@@ -4,19 +4,29 @@ extern crate proc_macro;
 pub(crate) mod parser;
 pub(crate) mod generator;
 
-use parser::Model;
+use parser::{adapter_options::AdapterOptions, Model};
 use syn::parse2;
 
 /// Defines a dioxus renderer for bevy.
 ///
+/// Text nodes are spawned with a `SptsDioxusText` component unless a component that implements
+/// `From<String>` is passed with the `text` option, i.e. `#[bevy_spts_dioxus(text = Text2d)]`.
+/// Placeholders are spawned with just a `SptsDioxusPlaceholder` unless a
+/// `fn(&mut World) -> Entity` is passed with the `placeholder` option.
+///
 /// * `input`:
 #[proc_macro_attribute]
-pub fn bevy_spts_dioxus(_attr: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+pub fn bevy_spts_dioxus(attr: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let options = match parse2::<AdapterOptions>(proc_macro2::TokenStream::from(attr)) {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let input = proc_macro2::TokenStream::from(input);
-    let model = match parse2::<Model>(input) {
+    let mut model = match parse2::<Model>(input) {
         Ok(model) => model,
         Err(err) => return err.to_compile_error().into(),
     };
+    model.options = options;
 
     let tokens = match generator::generate(&model) {
         Ok(tokens) => tokens,
@@ -30,7 +40,7 @@ pub fn bevy_spts_dioxus(_attr: proc_macro::TokenStream, input: proc_macro::Token
 /// Defines a new element to be used in the dioxus bevy renderer.
 ///
 /// ## Example
-/// ```rust
+/// ```rust,ignore
 /// #[define_attr]
 /// fn position_x_attr(mut entity_mut: EntityWorldMut, value: AttributeValue) {
 ///     entity_mut.get::<Transform>().unwrap().translation.x = value.as_f32().unwrap_or(0.);
@@ -54,12 +64,18 @@ pub fn define_element(attr: proc_macro::TokenStream, input: proc_macro::TokenStr
 #[proc_macro_attribute]
 /// Defines an attribute that can later be used in an element definition.
 ///
+/// When the attribute is removed (set to `None`) the attribute fn is called with
+/// `AttributeValue::None`, unless a remove fn is provided with `#[define_attr(remove = ...)]`.
+///
 /// ## Example
-/// ```rust
-/// #[define_attr]
+/// ```rust,ignore
+/// #[define_attr(remove = reset_position_x)]
 /// fn position_x_attr(mut entity_mut: EntityWorldMut, value: AttributeValue) {
 ///     entity_mut.get::<Transform>().unwrap().translation.x = value.as_f32().unwrap_or(0.);
 /// }
+/// fn reset_position_x(world: &mut World, entity: Entity) {
+///     world.get_mut::<Transform>(entity).unwrap().translation.x = 0.;
+/// }
 ///
 /// pub mod dioxus_elements {
 ///     #[define_element]
@@ -69,9 +85,7 @@ pub fn define_element(attr: proc_macro::TokenStream, input: proc_macro::TokenStr
 ///     }
 /// }
 /// ```
-pub fn define_attr(attr: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let mut out = proc_macro::TokenStream::new();
-    out.extend(attr);
-    out.extend(input);
-    out
+pub fn define_attr(_attr: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    // Options such as `remove = ...` are read by `#[bevy_spts_dioxus]`.
+    input
 }
@@ -0,0 +1,44 @@
+use syn::{parse::Parse, ExprPath, Ident, Token, TypePath};
+
+/// Options passed to the macro, i.e. `#[bevy_spts_dioxus(text = Text2d)]`.
+#[derive(Debug, Default)]
+pub struct AdapterOptions {
+    /// Component that text nodes are spawned with, must implement `From<String>`.
+    pub text_component: Option<TypePath>,
+    /// `fn(&mut World) -> Entity` that placeholders are spawned with.
+    pub placeholder_fn: Option<ExprPath>,
+}
+
+const UNEXPECTED_OPTION_ERROR_MESSAGE: &str = r#"Unexpected option.  Currently only the `text` and `placeholder` options are supported, i.e.:
+#[bevy_spts_dioxus(text = Text2d, placeholder = spawn_placeholder)]
+pub mod my_adapter {
+    ...
+}
+"#;
+
+impl Parse for AdapterOptions {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut options = AdapterOptions::default();
+
+        while !input.is_empty() {
+            let option_ident = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            if option_ident == "text" {
+                options.text_component = Some(input.parse::<TypePath>()?);
+            } else if option_ident == "placeholder" {
+                options.placeholder_fn = Some(input.parse::<ExprPath>()?);
+            } else {
+                return Err(syn::Error::new(
+                    option_ident.span(),
+                    UNEXPECTED_OPTION_ERROR_MESSAGE,
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(options)
+    }
+}
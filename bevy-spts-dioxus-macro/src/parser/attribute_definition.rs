@@ -0,0 +1,33 @@
+use syn::{Ident, ItemFn, Meta};
+
+#[derive(Debug)]
+pub struct AttributeDefinition {
+    pub item_fn: ItemFn,
+    /// Called instead of the attribute fn when the attribute is removed (set to `None`), i.e.
+    /// `#[define_attr(remove = reset_position)]`.
+    pub remove_handler: Option<Ident>,
+}
+
+impl TryFrom<&ItemFn> for AttributeDefinition {
+    type Error = syn::Error;
+    fn try_from(value: &ItemFn) -> Result<Self, Self::Error> {
+        let mut remove_handler = None;
+        for attr in value.attrs.iter().filter(|attr| attr.path().is_ident("define_attr")) {
+            if let Meta::List(_) = &attr.meta {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("remove") {
+                        remove_handler = Some(meta.value()?.parse::<Ident>()?);
+                        Ok(())
+                    } else {
+                        Err(meta.error("Unexpected attribute option.  Currently only `#[define_attr(remove = my_remove_fn)]` is supported."))
+                    }
+                })?;
+            }
+        }
+
+        Ok(AttributeDefinition {
+            item_fn: value.clone(),
+            remove_handler,
+        })
+    }
+}
@@ -1,4 +1,6 @@
-use syn::{spanned::Spanned, Field, Ident, Item, ItemMod, ItemStruct, Type, TypePath};
+use std::collections::{HashMap, HashSet};
+
+use syn::{spanned::Spanned, Field, Ident, Item, ItemMod, ItemStruct, Meta, Type, TypePath};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct ElementAttribute {
@@ -27,10 +29,21 @@ impl TryFrom<&Field> for ElementAttribute {
     }
 }
 
+/// What happens to a `#[component]` when its attribute is removed (set to `None`).
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum ComponentRemoveBehaviour {
+    /// Reset the component to the value it was spawned with, i.e. `#[component]`.
+    #[default]
+    Reset,
+    /// Remove the component from the entity, i.e. `#[component(remove)]`.
+    Remove,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct ElementComponent {
     pub field_ident: Ident,
     pub component_type: TypePath,
+    pub remove_behaviour: ComponentRemoveBehaviour,
 }
 
 impl TryFrom<&Field> for ElementComponent {
@@ -39,11 +52,25 @@ impl TryFrom<&Field> for ElementComponent {
         let field_ident = value.ident
             .clone()
             .ok_or_else(|| syn::Error::new(value.span(), "Found field without an identifier.  This is usually caused by tuple structs, please convert to a normal struct."))?;
+        let mut remove_behaviour = ComponentRemoveBehaviour::default();
+        for attr in value.attrs.iter().filter(|attr| attr.path().is_ident("component")) {
+            if let Meta::List(_) = &attr.meta {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("remove") {
+                        remove_behaviour = ComponentRemoveBehaviour::Remove;
+                        Ok(())
+                    } else {
+                        Err(meta.error("Unexpected component option.  Currently only `#[component(remove)]` is supported."))
+                    }
+                })?;
+            }
+        }
         match &value.ty {
             Type::Path(type_path) => {
                 Ok(Self {
                     field_ident,
                     component_type: type_path.clone(),
+                    remove_behaviour,
                 })
             },
             other => Err(syn::Error::new(other.span(), "Expected a type path such as 'Transform' or 'Handle<Mesh>'."))
@@ -51,14 +78,61 @@ impl TryFrom<&Field> for ElementComponent {
     }
 }
 
+/// Events that every element has, sent by the focus manager with these payloads.  They can't be
+/// declared as `#[event]` fields as the focus manager would still send its own payload.
+pub const BUILT_IN_EVENTS: [(&str, &str); 3] = [
+    ("onfocus", "SptsDioxusFocusData"),
+    ("onblur", "SptsDioxusFocusData"),
+    ("onkeydown", "SptsDioxusKeyData"),
+];
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ElementEvent {
+    pub field_ident: Ident,
+    pub event_type: TypePath,
+}
+
+impl ElementEvent {
+    /// Name of the event as dioxus passes it to the renderer, i.e. `press` for `onpress`.
+    pub fn event_name(&self) -> String {
+        self.field_ident.to_string()[2..].to_string()
+    }
+}
+
+impl TryFrom<&Field> for ElementEvent {
+    type Error = syn::Error;
+    fn try_from(value: &Field) -> Result<Self, Self::Error> {
+        let field_ident = value.ident
+            .clone()
+            .ok_or_else(|| syn::Error::new(value.span(), "Found field without an identifier.  This is usually caused by tuple structs, please convert to a normal struct."))?;
+        let field_name = field_ident.to_string();
+        if !field_name.starts_with("on") || field_name.len() <= 2 {
+            return Err(syn::Error::new(field_ident.span(), format!("Event fields must start with 'on' so rsx treats them as event listeners, i.e. 'on{field_name}'.")));
+        }
+        if let Some((_, payload)) = BUILT_IN_EVENTS.iter().find(|(name, _)| *name == field_name) {
+            return Err(syn::Error::new(field_ident.span(), format!("'{field_name}' is a built-in event that the focus manager sends with '{payload}' to every element, it can't be declared as an #[event] field.")));
+        }
+        match &value.ty {
+            Type::Path(type_path) => {
+                Ok(Self {
+                    field_ident,
+                    event_type: type_path.clone(),
+                })
+            },
+            other => Err(syn::Error::new(other.span(), "Expected a type path to a bevy event such as 'PressEvent'."))
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ElementDefinition {
     pub ident: Ident,
     pub attributes: Vec<ElementAttribute>,
     pub components: Vec<ElementComponent>,
+    pub events: Vec<ElementEvent>,
 }
 
-const UNEXPECTED_FIELD_ERROR_MESSAGE: &str = r#"Unexpected field.  Currently only #[attr] and #[component] fields are supported, i.e.:
+const UNEXPECTED_FIELD_ERROR_MESSAGE: &str = r#"Unexpected field.  Currently only #[attr], #[component] and #[event] fields are supported, i.e.:
 #[define_attr]
 fn position_x_attr(mut entity_mut: EntityWorldMut, value: AttributeValue) {
     entity_mut.get::<Transform>().unwrap().translation.x = value.as_f32().unwrap_or(0.);
@@ -67,14 +141,23 @@ fn position_x_attr(mut entity_mut: EntityWorldMut, value: AttributeValue) {
 #[derive(Component, PartialEq)]
 pub struct MyComp(1)
 
+#[derive(Event, Clone)]
+pub struct PressEvent;
+impl SptsDioxusElementEvent for PressEvent {}
+
 pub mod dioxus_elements {
     #[define_element]
     struct my_element {
+        // Reset to the value it was spawned with when the attribute is removed, use
+        // `#[component(remove)]` to remove the component instead.
         #[component]
         my_comp: MyComp,
 
         #[attr]
         position_x: position_x_attr,
+
+        #[event]
+        onpress: PressEvent,
     }
 }
 "#;
@@ -88,6 +171,7 @@ impl TryFrom<&ItemStruct> for ElementDefinition {
             ident: value.ident.clone(),
             attributes: vec![],
             components: vec![],
+            events: vec![],
         };
 
         for field in value.fields.iter() {
@@ -103,6 +187,10 @@ impl TryFrom<&ItemStruct> for ElementDefinition {
                 element_definition
                     .components
                     .push(ElementComponent::try_from(field)?);
+            } else if field.attrs.iter().any(|attr| attr.path().is_ident("event")) {
+                element_definition
+                    .events
+                    .push(ElementEvent::try_from(field)?);
             } else {
                 return Err(syn::Error::new(
                     field.span(),
@@ -134,25 +222,89 @@ impl TryFrom<&ItemMod> for DioxusElementsModule {
         for item in content_items {
             #[allow(clippy::single_match)]
             match &item {
-                syn::Item::Struct(item_struct) => {
+                syn::Item::Struct(item_struct)
                     if item_struct
                         .attrs
                         .iter()
-                        .any(|attr| attr.path().is_ident("define_element"))
-                    {
-                        let element_definition = ElementDefinition::try_from(item_struct)?;
-                        element_definitons.push(element_definition);
-                        continue;
-                    }
+                        .any(|attr| attr.path().is_ident("define_element")) =>
+                {
+                    let element_definition = ElementDefinition::try_from(item_struct)?;
+                    element_definitons.push(element_definition);
+                    continue;
                 }
                 _ => {}
             }
             pass_through_items.push(item.clone())
         }
 
-        Ok(DioxusElementsModule {
+        let dioxus_elements_module = DioxusElementsModule {
             element_definitions: element_definitons,
             pass_through_items,
+        };
+
+        // Events are declared in a single `dioxus_elements::events` module so the same event name
+        // can't have different payloads on different elements.
+        let mut events: HashMap<String, &ElementEvent> = HashMap::new();
+        for el_event in dioxus_elements_module.element_definitions.iter().flat_map(|el_def| &el_def.events) {
+            let name = el_event.field_ident.to_string();
+            match events.get(&name) {
+                Some(existing) if existing.event_type != el_event.event_type => {
+                    return Err(syn::Error::new(
+                        el_event.event_type.span(),
+                        format!("Event '{name}' is already defined with a different event type on another element."),
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    events.insert(name, el_event);
+                }
+            }
+        }
+
+        Ok(dioxus_elements_module)
+    }
+}
+
+impl DioxusElementsModule {
+    /// All events defined across all elements, deduplicated by name.
+    pub fn unique_events(&self) -> Vec<&ElementEvent> {
+        let mut names = HashSet::new();
+        self.element_definitions
+            .iter()
+            .flat_map(|el_def| &el_def.events)
+            .filter(|el_event| names.insert(el_event.field_ident.to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, ItemStruct};
+
+    use super::ElementEvent;
+
+    fn event_field(item: ItemStruct) -> Result<ElementEvent, syn::Error> {
+        ElementEvent::try_from(item.fields.iter().next().unwrap())
+    }
+
+    #[test]
+    fn built_in_events_cant_be_declared() {
+        let err = event_field(parse_quote! {
+            struct button {
+                #[event]
+                onkeydown: MyKey,
+            }
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("'onkeydown' is a built-in event"), "{err}");
+
+        let event = event_field(parse_quote! {
+            struct button {
+                #[event]
+                onpress: PressEvent,
+            }
         })
+        .unwrap();
+        assert_eq!(event.event_name(), "press");
     }
 }
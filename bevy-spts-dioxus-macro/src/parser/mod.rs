@@ -1,14 +1,19 @@
+pub(crate) mod adapter_options;
+pub(crate) mod attribute_definition;
 pub(crate) mod element_definition;
 
+use adapter_options::AdapterOptions;
+use attribute_definition::AttributeDefinition;
 use element_definition::DioxusElementsModule;
 use std::collections::HashMap;
-use syn::{parse::Parse, spanned::Spanned, Ident, Item, ItemFn, ItemMod};
+use syn::{parse::Parse, spanned::Spanned, Ident, Item, ItemMod};
 
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct Model {
     pub module_ident: Ident,
-    pub attribute_definitions: HashMap<String, ItemFn>,
+    pub options: AdapterOptions,
+    pub attribute_definitions: HashMap<String, AttributeDefinition>,
     pub dioxus_elements_module: DioxusElementsModule,
     pub pass_through_items: Vec<Item>,
 }
@@ -48,30 +53,26 @@ impl Parse for Model {
 
         for item in content_items {
             match &item {
-                syn::Item::Fn(item_fn) => {
+                syn::Item::Fn(item_fn)
                     if item_fn
                         .attrs
                         .iter()
-                        .any(|attr| attr.path().is_ident("define_attr"))
-                    {
-                        attribute_definitions
-                            .insert(item_fn.sig.ident.to_string(), item_fn.clone());
-                        continue;
-                    }
+                        .any(|attr| attr.path().is_ident("define_attr")) =>
+                {
+                    attribute_definitions
+                        .insert(item_fn.sig.ident.to_string(), AttributeDefinition::try_from(item_fn)?);
+                    continue;
                 }
-                syn::Item::Mod(item_mod) => {
-                    let is_dioxus_elements_module = item_mod.ident == "dioxus_elements";
+                syn::Item::Mod(item_mod) if item_mod.ident == "dioxus_elements" => {
                     let has_public_visibility = matches!(item_mod.vis, syn::Visibility::Public(_));
-                    if is_dioxus_elements_module {
-                        if !has_public_visibility {
-                            return Err(syn::Error::new(
-                                item_mod.vis.span(),
-                                "The 'dioxus_elements' module must have public visibility.",
-                            ));
-                        }
-                        dioxus_elements_module = Some(DioxusElementsModule::try_from(item_mod)?);
-                        continue;
+                    if !has_public_visibility {
+                        return Err(syn::Error::new(
+                            item_mod.vis.span(),
+                            "The 'dioxus_elements' module must have public visibility.",
+                        ));
                     }
+                    dioxus_elements_module = Some(DioxusElementsModule::try_from(item_mod)?);
+                    continue;
                 }
                 _ => {}
             }
@@ -84,6 +85,7 @@ impl Parse for Model {
 
         Ok(Model {
             module_ident,
+            options: AdapterOptions::default(),
             attribute_definitions,
             dioxus_elements_module,
             pass_through_items,
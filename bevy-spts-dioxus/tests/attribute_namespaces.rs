@@ -0,0 +1,106 @@
+//! Elements that use the same attribute key for different components and handlers.
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_spts_dioxus::*;
+
+#[derive(Component, Default, Clone, PartialEq, Debug)]
+struct Width(i32);
+
+#[derive(Component, Default, Clone, PartialEq, Debug)]
+struct Height(i32);
+
+#[derive(Component, Clone, PartialEq, Debug)]
+struct Label(String);
+
+#[derive(Component, Clone, PartialEq, Debug)]
+struct Title(String);
+
+#[bevy_spts_dioxus]
+pub mod adapter {
+    use super::{Height, Label, Title, Width};
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+    use bevy_spts_dioxus::*;
+    use dioxus_core::AttributeValue;
+
+    #[define_attr]
+    pub fn label_attr(world: &mut World, entity: Entity, value: &AttributeValue) {
+        if let Some(label) = value.as_string() {
+            world.entity_mut(entity).insert(Label(label.clone()));
+        }
+    }
+
+    #[define_attr]
+    pub fn title_attr(world: &mut World, entity: Entity, value: &AttributeValue) {
+        if let Some(title) = value.as_string() {
+            world.entity_mut(entity).insert(Title(title.clone()));
+        }
+    }
+
+    pub mod dioxus_elements {
+        use bevy_spts_dioxus::SptsDioxusElement;
+
+        #[define_element]
+        pub struct column {
+            #[component]
+            size: Width,
+            #[attr]
+            text: label_attr,
+        }
+        impl SptsDioxusElement for column {}
+
+        #[define_element]
+        pub struct row {
+            #[component]
+            size: Height,
+            #[attr]
+            text: title_attr,
+        }
+        impl SptsDioxusElement for row {}
+    }
+}
+use adapter::*;
+
+#[derive(Resource, Clone)]
+struct Size(i32);
+
+#[component]
+fn root() -> Element {
+    let size = Hooks::use_bevy_resource::<Size>().0;
+    rsx! {
+        column {
+            size: WA(Width(size)),
+            text: "column",
+            row {
+                size: WA(Height(size * 2)),
+                text: "row",
+            }
+        }
+    }
+}
+
+fn single<C: bevy_ecs::component::Component + Clone>(app: &mut App) -> C {
+    let world = app.world_mut();
+    world.query::<&C>().single(world).clone()
+}
+
+#[test]
+fn elements_apply_the_same_key_to_their_own_components() {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    app.world_mut().spawn(SptsDioxusRootComponent::new(root));
+    app.insert_resource(Size(1));
+    app.update();
+
+    assert_eq!(single::<Width>(&mut app), Width(1));
+    assert_eq!(single::<Height>(&mut app), Height(2));
+    assert_eq!(single::<Label>(&mut app), Label("column".to_string()));
+    assert_eq!(single::<Title>(&mut app), Title("row".to_string()));
+
+    app.insert_resource(Size(3));
+    app.update();
+    assert_eq!(single::<Width>(&mut app), Width(3));
+    assert_eq!(single::<Height>(&mut app), Height(6));
+}
@@ -0,0 +1,77 @@
+//! Adapter shared by the integration tests.
+#![allow(dead_code)]
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_reflect::Reflect;
+use bevy_spts_dioxus::*;
+
+#[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+pub struct Value(pub i32);
+
+#[bevy_spts_dioxus]
+pub mod adapter {
+    use super::Value;
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+    use bevy_spts_dioxus::*;
+
+    pub mod dioxus_elements {
+        use bevy_spts_dioxus::SptsDioxusElement;
+
+        #[define_element]
+        pub struct node {
+            #[component]
+            value: Value,
+        }
+        impl SptsDioxusElement for node {}
+    }
+}
+use adapter::*;
+
+/// Number of items the list components render.
+#[derive(Resource, Clone)]
+pub struct Count(pub i32);
+
+/// A `node` with [Count] keyed children.
+#[component]
+pub fn list() -> Element {
+    let count = Hooks::use_bevy_resource::<Count>().0;
+    rsx! {
+        node {
+            for i in 1..=count {
+                node { key: "{i}", value: WA(Value(i)) }
+            }
+        }
+    }
+}
+
+/// Like [list], with a focusable parent and text in each item.
+#[component]
+pub fn labelled_list() -> Element {
+    let count = Hooks::use_bevy_resource::<Count>().0;
+    rsx! {
+        node {
+            value: WA(Value(0)),
+            tabindex: 0,
+            for i in 1..=count {
+                node { key: "{i}", value: WA(Value(i)), "item {i}" }
+            }
+        }
+    }
+}
+
+pub fn app_with_root(root: fn() -> Element) -> App {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    app.world_mut().spawn(SptsDioxusRootComponent::new(root));
+    app
+}
+
+pub fn rendered_values(app: &mut App) -> Vec<i32> {
+    let world = app.world_mut();
+    let mut values: Vec<i32> = world.query::<&Value>().iter(world).map(|v| v.0).collect();
+    values.sort();
+    values
+}
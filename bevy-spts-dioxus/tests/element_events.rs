@@ -0,0 +1,136 @@
+//! User defined `#[event]` fields, forwarded from an `EventWriter` or from triggers.
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::Value;
+
+#[bevy_spts_dioxus]
+pub mod adapter {
+    use super::common::Value;
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+    use bevy_spts_dioxus::*;
+
+    #[derive(Event, Clone, Debug)]
+    pub struct PressEvent {
+        pub entity: Entity,
+        pub strength: i32,
+    }
+    impl SptsDioxusElementEvent for PressEvent {
+        fn target(&self) -> Option<Entity> {
+            Some(self.entity)
+        }
+    }
+
+    pub mod dioxus_elements {
+        use bevy_spts_dioxus::SptsDioxusElement;
+
+        #[define_element]
+        pub struct button {
+            #[component]
+            value: Value,
+            #[event]
+            onpress: PressEvent,
+        }
+        impl SptsDioxusElement for button {}
+
+        // Declares the same event, it's still only forwarded once.
+        #[define_element]
+        pub struct toggle {
+            #[component]
+            value: Value,
+            #[event]
+            onpress: PressEvent,
+        }
+        impl SptsDioxusElement for toggle {}
+    }
+}
+use adapter::*;
+
+#[derive(Resource, Default)]
+struct Presses(Vec<i32>);
+
+#[component]
+fn root() -> Element {
+    let presses = Hooks::use_resource_mut::<Presses>();
+    rsx! {
+        button {
+            value: WA(Value(0)),
+            onpress: move |e: DioxusEvent<PressEvent>| {
+                let strength = e.strength;
+                presses.write(move |presses| presses.0.push(strength));
+            },
+            // Presses on the child bubble up to the button's listener.
+            toggle { value: WA(Value(1)) }
+        }
+    }
+}
+
+fn app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    app.world_mut().spawn(SptsDioxusRootComponent::new(root));
+    app.init_resource::<Presses>();
+    app.update();
+
+    let world = app.world_mut();
+    let child = world
+        .query::<(Entity, &Value)>()
+        .iter(world)
+        .find_map(|(entity, value)| (value.0 == 1).then_some(entity))
+        .unwrap();
+    (app, child)
+}
+
+fn take_presses(app: &mut App) -> Vec<i32> {
+    std::mem::take(&mut app.world_mut().resource_mut::<Presses>().0)
+}
+
+#[test]
+fn events_sent_with_an_event_writer_reach_listeners() {
+    let (mut app, child) = app();
+    app.world_mut().send_event(PressEvent {
+        entity: child,
+        strength: 3,
+    });
+    app.update();
+    app.update();
+    assert_eq!(take_presses(&mut app), vec![3]);
+}
+
+#[test]
+fn triggered_events_reach_listeners() {
+    let (mut app, child) = app();
+    let event = PressEvent {
+        entity: Entity::PLACEHOLDER,
+        strength: 5,
+    };
+    app.world_mut().trigger_targets(event, child);
+    app.update();
+    app.update();
+    assert_eq!(take_presses(&mut app), vec![5]);
+}
+
+#[test]
+fn registering_an_event_again_forwards_it_once() {
+    let (mut app, child) = app();
+    register_element_event::<PressEvent>(&mut app, "press");
+    app.world_mut().send_event(PressEvent {
+        entity: child,
+        strength: 1,
+    });
+    app.world_mut().trigger_targets(
+        PressEvent {
+            entity: Entity::PLACEHOLDER,
+            strength: 2,
+        },
+        child,
+    );
+    app.update();
+    app.update();
+    assert_eq!(take_presses(&mut app), vec![2, 1]);
+}
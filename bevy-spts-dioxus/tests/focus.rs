@@ -0,0 +1,152 @@
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_input::{
+    gamepad::{Gamepad, GamepadButton},
+    keyboard::KeyCode,
+    ButtonInput,
+};
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, app_with_root, Value};
+
+#[derive(Resource, Default)]
+struct Log(Vec<String>);
+
+#[component]
+fn Focusable(id: i32, tab_index: i64, focus_on_c: Option<Signal<Option<Entity>>>) -> Element {
+    let log = Hooks::use_resource_mut::<Log>();
+    let focus = Hooks::use_focus();
+    let push = move |entry: String| log.write(move |log| log.0.push(entry));
+
+    rsx! {
+        node {
+            value: WA(Value(id)),
+            tabindex: tab_index,
+            onfocus: {
+                let push = push.clone();
+                move |_| push(format!("focus {id}"))
+            },
+            onblur: {
+                let push = push.clone();
+                move |_| push(format!("blur {id}"))
+            },
+            onkeydown: move |e: DioxusEvent<SptsDioxusKeyData>| {
+                push(format!("key {id} {:?}", e.key));
+                if e.key == SptsDioxusKey::Keyboard(KeyCode::KeyC) {
+                    if let Some(entity) = focus_on_c.and_then(|entity| entity()) {
+                        focus.focus(entity);
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[component]
+fn menu() -> Element {
+    let third = Hooks::use_entity();
+    rsx! {
+        Focusable { id: 1, tab_index: 0 }
+        Focusable { id: 2, tab_index: 0 }
+        node {
+            entity: third,
+            value: WA(Value(3)),
+            tabindex: -1,
+        }
+        // Positive indices come first in the tab order.
+        Focusable { id: 4, tab_index: 1, focus_on_c: *third }
+    }
+}
+
+fn take_log(app: &mut App) -> Vec<String> {
+    std::mem::take(&mut app.world_mut().resource_mut::<Log>().0)
+}
+
+fn press(app: &mut App, key: KeyCode) {
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(key);
+    app.update();
+    let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    keys.release(key);
+    keys.clear();
+}
+
+fn focused_value(app: &mut App) -> Option<i32> {
+    let focused = app.world().resource::<SptsDioxusFocus>().focused()?;
+    app.world().get::<Value>(focused).map(|value| value.0)
+}
+
+#[test]
+fn tab_moves_focus_and_routes_key_events() {
+    let mut app = app_with_root(menu);
+    app.init_resource::<Log>();
+    app.init_resource::<ButtonInput<KeyCode>>();
+    app.update();
+    assert_eq!(focused_value(&mut app), None);
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(focused_value(&mut app), Some(4));
+    assert_eq!(take_log(&mut app), vec!["focus 4"]);
+
+    // The key goes to the entity that was focused when it was pressed.
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(focused_value(&mut app), Some(1));
+    assert_eq!(
+        take_log(&mut app),
+        vec!["key 4 Keyboard(Tab)", "blur 4", "focus 1"]
+    );
+
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(focused_value(&mut app), Some(4));
+    take_log(&mut app);
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::ShiftLeft);
+    press(&mut app, KeyCode::Tab);
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .release(KeyCode::ShiftLeft);
+    assert_eq!(focused_value(&mut app), Some(2));
+    take_log(&mut app);
+}
+
+#[test]
+fn focus_moves_programmatically_and_with_gamepads() {
+    let mut app = app_with_root(menu);
+    app.init_resource::<Log>();
+    app.init_resource::<ButtonInput<KeyCode>>();
+    app.update();
+    press(&mut app, KeyCode::Tab);
+    take_log(&mut app);
+
+    // The handler's focus change is applied after the render and sent on the next frame.
+    press(&mut app, KeyCode::KeyC);
+    app.update();
+    assert_eq!(focused_value(&mut app), Some(3));
+    assert_eq!(take_log(&mut app), vec!["key 4 Keyboard(KeyC)", "blur 4"]);
+
+    // Entities with a negative tab index aren't in the tab order.
+    let gamepad = app.world_mut().spawn(Gamepad::default()).id();
+    let mut gamepad_entity = app.world_mut().entity_mut(gamepad);
+    let mut gamepad_input = gamepad_entity.get_mut::<Gamepad>().unwrap();
+    gamepad_input.digital_mut().press(GamepadButton::DPadDown);
+    app.update();
+    let mut gamepad_entity = app.world_mut().entity_mut(gamepad);
+    gamepad_entity
+        .get_mut::<Gamepad>()
+        .unwrap()
+        .digital_mut()
+        .reset_all();
+    assert_eq!(focused_value(&mut app), Some(4));
+    assert_eq!(take_log(&mut app), vec!["focus 4"]);
+
+    // Focus is lost when the focused entity is despawned.
+    let focused = app.world().resource::<SptsDioxusFocus>().focused().unwrap();
+    app.world_mut().despawn(focused);
+    app.update();
+    assert_eq!(focused_value(&mut app), None);
+}
@@ -0,0 +1,209 @@
+//! Exercises the hooks' access to the world.
+
+use std::cell::RefCell;
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, rendered_values, Value};
+
+#[derive(Resource, Clone)]
+struct Counter(i32);
+
+#[derive(Component)]
+struct Spawned;
+
+fn app_with_root(root: fn() -> Element) -> App {
+    let mut app = common::app_with_root(root);
+    app.insert_resource(Counter(0));
+    app
+}
+
+#[component]
+fn reads_world() -> Element {
+    let counter = Hooks::use_bevy_resource::<Counter>().0;
+    let mut spawned = Hooks::use_query_filtered::<Entity, With<Spawned>>();
+    let spawned = spawned.query(|query| query.iter().count() as i32);
+    let entity = Hooks::use_entity();
+    let value = Hooks::use_component::<Value>(entity).map_or(-1, |value| value.0);
+    let memo = Hooks::use_world_memo(|world| world.resource::<Counter>().0 * 10);
+
+    rsx! {
+        node { value: WA(Value(counter)) }
+        node { value: WA(Value(spawned)) }
+        node { entity, value: WA(Value(value)) }
+        node { value: WA(Value(memo())) }
+    }
+}
+
+#[test]
+fn hooks_read_world_while_rendering() {
+    let mut app = app_with_root(reads_world);
+    app.update();
+    app.update();
+    assert_eq!(rendered_values(&mut app), vec![-1, 0, 0, 0]);
+
+    app.world_mut().resource_mut::<Counter>().0 = 2;
+    app.world_mut().spawn(Spawned);
+    app.update();
+    // The node reading its own `Value` stays at -1 and the memo doesn't track the world.
+    assert_eq!(rendered_values(&mut app), vec![-1, 0, 1, 2]);
+}
+
+#[component]
+fn writes_world() -> Element {
+    let counter = Hooks::use_bevy_resource::<Counter>().0;
+    let counter_mut = Hooks::use_resource_mut::<Counter>();
+    let commands = Hooks::use_commands();
+    let scheduler = use_system_scheduler::<SptsDioxusAdapter>();
+    if counter < 2 {
+        counter_mut.write(|counter| counter.0 += 1);
+    }
+    use_hook(|| {
+        commands.with(|commands| {
+            commands.spawn(Spawned);
+        });
+        scheduler.schedule(|mut commands: Commands| {
+            commands.spawn(Spawned);
+        });
+    });
+
+    rsx! {
+        node { value: WA(Value(counter)) }
+    }
+}
+
+#[test]
+fn hooks_write_world_after_rendering() {
+    let mut app = app_with_root(writes_world);
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<Counter>().0, 2);
+    assert_eq!(rendered_values(&mut app), vec![2]);
+    let world = app.world_mut();
+    assert_eq!(world.query::<&Spawned>().iter(world).count(), 2);
+}
+
+#[component]
+fn subscribes() -> Element {
+    let counter = Hooks::use_bevy_resource::<Counter>().0;
+    let _resources = Hooks::use_world(|world| world.storages().resources.len());
+    let _events = Hooks::use_event_reader::<SptsDioxusError>();
+    let mut query = Hooks::use_query::<&Value>();
+    let _values = query.query(|query| query.iter().count());
+
+    rsx! {
+        node { value: WA(Value(counter)) }
+    }
+}
+
+#[test]
+fn root_teardown_unsubscribes_hooks() {
+    let mut app = app_with_root(subscribes);
+    app.update();
+    app.update();
+    let world = app.world_mut();
+    let root = world
+        .query_filtered::<Entity, With<SptsDioxusRootComponent>>()
+        .single(world);
+    app.world_mut().despawn(root);
+    app.update();
+    assert!(rendered_values(&mut app).is_empty());
+
+    app.world_mut()
+        .spawn(SptsDioxusRootComponent::new(subscribes));
+    app.world_mut().resource_mut::<Counter>().0 = 3;
+    app.update();
+    assert_eq!(rendered_values(&mut app), vec![3]);
+}
+
+fn times_ten_plus_runs(In(value): In<i32>, mut runs: Local<i32>) -> i32 {
+    *runs += 1;
+    value * 10 + *runs
+}
+
+#[component]
+fn schedules_with_result() -> Element {
+    let scheduler = use_system_scheduler::<SptsDioxusAdapter>();
+    let result = use_signal(|| None);
+    match result() {
+        None => scheduler.schedule_with(times_ten_plus_runs, 1, result),
+        Some(11) => scheduler.schedule_with(times_ten_plus_runs, 2, result),
+        Some(_) => {}
+    }
+
+    rsx! {
+        node { value: WA(Value(result().unwrap_or(0))) }
+    }
+}
+
+#[test]
+fn scheduled_system_results_are_written_to_signal() {
+    let mut app = app_with_root(schedules_with_result);
+    for _ in 0..5 {
+        app.update();
+    }
+    // The system's `Local` is kept between runs as it's only initialized once.
+    assert_eq!(rendered_values(&mut app), vec![22]);
+}
+
+thread_local! {
+    static ESCAPED_SCHEDULER: RefCell<Option<DeferredSystemScheduler>> = const { RefCell::new(None) };
+}
+
+#[component]
+fn leaks_scheduler() -> Element {
+    let scheduler = use_system_scheduler::<SptsDioxusAdapter>();
+    ESCAPED_SCHEDULER.with(|escaped| *escaped.borrow_mut() = Some(scheduler));
+    rsx! {
+        node {}
+    }
+}
+
+#[test]
+#[should_panic(expected = "can only be accessed while the root is rendering")]
+fn world_access_outside_of_rendering_panics() {
+    let mut app = app_with_root(leaks_scheduler);
+    app.update();
+    let scheduler = ESCAPED_SCHEDULER.with(|escaped| escaped.borrow_mut().take().unwrap());
+    scheduler.schedule(|| {});
+}
+
+thread_local! {
+    static NESTED_WRITE_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+#[component]
+fn writes_while_querying() -> Element {
+    let mut query = Hooks::use_query::<&Value>();
+    let spawn = Hooks::use_world_callback(|world, ()| {
+        world.spawn(Spawned);
+    });
+    // Dioxus catches panics while rendering, catch it here to check the message.
+    let nested = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        query.query(|_| spawn.call(()))
+    }));
+    let message = nested.err().and_then(|err| err.downcast::<String>().ok());
+    NESTED_WRITE_PANIC.with(|panic| *panic.borrow_mut() = message.map(|message| *message));
+    // The world can be accessed mutably again once the query closure returned.
+    spawn.call(());
+    rsx! {
+        node {}
+    }
+}
+
+#[test]
+fn mutable_world_access_while_reading_panics() {
+    let mut app = app_with_root(writes_while_querying);
+    app.update();
+    let message = NESTED_WRITE_PANIC.with(|panic| panic.borrow_mut().take());
+    assert!(
+        message.is_some_and(|message| message.contains("can't be mutably accessed")),
+        "with_world_mut didn't panic inside of a query"
+    );
+    let world = app.world_mut();
+    assert_eq!(world.query::<&Spawned>().iter(world).count(), 1);
+}
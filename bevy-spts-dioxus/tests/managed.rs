@@ -0,0 +1,188 @@
+use bevy_ecs::{event::Events, prelude::*};
+use bevy_hierarchy::{BuildChildren, Children, DespawnRecursiveExt};
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, Value};
+
+#[component]
+fn list() -> Element {
+    rsx! {
+        node {
+            value: WA(Value(0)),
+            node { value: WA(Value(1)), "item" }
+        }
+    }
+}
+
+fn app_with_list() -> (bevy_app::App, Entity) {
+    let mut app = bevy_app::App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    let root = app
+        .world_mut()
+        .spawn(SptsDioxusRootComponent::new(list))
+        .id();
+    app.update();
+    (app, root)
+}
+
+fn errors(app: &bevy_app::App) -> Vec<SptsDioxusErrorKind> {
+    let events = app.world().resource::<Events<SptsDioxusError>>();
+    events
+        .get_cursor()
+        .read(events)
+        .map(|error| error.kind.clone())
+        .collect()
+}
+
+fn entity_with_value(app: &mut bevy_app::App, value: i32) -> Entity {
+    let world = app.world_mut();
+    world
+        .query::<(Entity, &Value)>()
+        .iter(world)
+        .find(|(_, v)| v.0 == value)
+        .unwrap()
+        .0
+}
+
+#[test]
+fn spawned_entities_are_marked_with_their_root() {
+    let (mut app, root) = app_with_list();
+    let world = app.world_mut();
+    let mut managed: Vec<SptsDioxusManaged> = world
+        .query_filtered::<&SptsDioxusManaged, WithSptsDioxusManaged>()
+        .iter(world)
+        .copied()
+        .collect();
+    managed.sort_by_key(|managed| managed.tag);
+    assert!(managed.iter().all(|managed| managed.root == root));
+    let tags: Vec<&str> = managed.iter().map(|managed| managed.tag).collect();
+    assert_eq!(tags, vec![SptsDioxusManaged::TEXT, "node", "node"]);
+    // Dioxus assigns ids to the nodes with dynamic attributes, the static text has none.
+    let elements: Vec<bool> = managed
+        .iter()
+        .map(|managed| managed.element.is_some())
+        .collect();
+    assert_eq!(elements, vec![false, true, true]);
+
+    let unmanaged = world
+        .query_filtered::<Entity, WithoutSptsDioxusManaged>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    assert_eq!(unmanaged, vec![root]);
+}
+
+#[test]
+fn reparenting_and_despawning_outside_of_dioxus_is_reported() {
+    let (mut app, _) = app_with_list();
+    app.insert_resource(SptsDioxusErrorPolicy::Skip);
+
+    let outside = app.world_mut().spawn_empty().id();
+    let item = entity_with_value(&mut app, 1);
+    app.world_mut().entity_mut(outside).add_child(item);
+    app.update();
+    assert_eq!(
+        errors(&app),
+        vec![SptsDioxusErrorKind::Reparented {
+            entity: item,
+            parent: Some(outside),
+        }]
+    );
+
+    let top = entity_with_value(&mut app, 0);
+    app.world_mut().entity_mut(top).despawn_recursive();
+    app.update();
+    assert!(errors(&app).contains(&SptsDioxusErrorKind::EntityDespawned(top)));
+}
+
+#[test]
+fn rebuild_policy_renders_the_root_again() {
+    let (mut app, root) = app_with_list();
+    app.insert_resource(SptsDioxusErrorPolicy::Skip);
+    app.insert_resource(SptsDioxusManagedPolicy::Rebuild);
+
+    let top = entity_with_value(&mut app, 0);
+    app.world_mut().entity_mut(top).despawn_recursive();
+    // Torn down and rendered again in the same frame.
+    app.update();
+    assert!(errors(&app).contains(&SptsDioxusErrorKind::EntityDespawned(top)));
+    let children = app.world().get::<Children>(root).unwrap();
+    assert_eq!(children.len(), 1);
+    let world = app.world_mut();
+    let mut values: Vec<i32> = world.query::<&Value>().iter(world).map(|v| v.0).collect();
+    values.sort();
+    assert_eq!(values, vec![0, 1]);
+}
+
+mod elements_with_their_own_children {
+    use super::Value;
+    use bevy_ecs::prelude::*;
+    use bevy_spts_dioxus::*;
+
+    /// Spawned by the element itself, not by dioxus.
+    #[derive(Component)]
+    pub struct Decoration;
+
+    #[bevy_spts_dioxus]
+    pub mod adapter {
+        use super::{Decoration, Value};
+        use bevy_app::App;
+        use bevy_ecs::prelude::*;
+        use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+        use bevy_spts_dioxus::*;
+
+        pub mod dioxus_elements {
+            use super::Decoration;
+            use bevy_ecs::world::{EntityWorldMut, World};
+            use bevy_hierarchy::BuildChildren;
+            use bevy_spts_dioxus::SptsDioxusElement;
+
+            #[define_element]
+            pub struct node {
+                #[component]
+                value: Value,
+            }
+            impl SptsDioxusElement for node {
+                fn spawn(world: &mut World) -> EntityWorldMut<'_> {
+                    let decoration = world.spawn(Decoration).id();
+                    let mut entity_mut = world.spawn_empty();
+                    entity_mut.add_child(decoration);
+                    entity_mut
+                }
+            }
+        }
+    }
+    use adapter::*;
+
+    #[component]
+    fn list() -> Element {
+        rsx! {
+            node {
+                node { value: WA(Value(1)) }
+                "item"
+            }
+        }
+    }
+
+    #[test]
+    fn only_the_template_nodes_are_marked() {
+        let mut app = bevy_app::App::new();
+        app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+        app.world_mut().spawn(SptsDioxusRootComponent::new(list));
+        app.update();
+
+        let world = app.world_mut();
+        let mut tags: Vec<&str> = world
+            .query::<&SptsDioxusManaged>()
+            .iter(world)
+            .map(|managed| managed.tag)
+            .collect();
+        tags.sort();
+        assert_eq!(tags, vec![SptsDioxusManaged::TEXT, "node", "node"]);
+        let decorations = world
+            .query_filtered::<(), (With<Decoration>, WithSptsDioxusManaged)>()
+            .iter(world)
+            .count();
+        assert_eq!(decorations, 0);
+    }
+}
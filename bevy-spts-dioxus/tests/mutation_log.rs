@@ -0,0 +1,223 @@
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::Children;
+use bevy_spts_dioxus::{
+    dioxus::dioxus_core::{ElementId, WriteMutations},
+    *,
+};
+
+mod common;
+use common::{adapter::*, labelled_list, rendered_values, Count, Value};
+
+/// Tags and text below `entity`, indented per level.
+fn hierarchy(world: &World, entity: Entity, depth: usize, out: &mut String) {
+    let Some(children) = world.get::<Children>(entity) else {
+        return;
+    };
+    for child in children.iter() {
+        let indent = "  ".repeat(depth);
+        if let Some(tag) = world.get::<SptsDioxusElementTag>(*child) {
+            out.push_str(&format!("{indent}{}\n", tag.0));
+        } else if let Some(text) = world.get::<SptsDioxusText>(*child) {
+            out.push_str(&format!("{indent}{:?}\n", text.0));
+        }
+        hierarchy(world, *child, depth + 1, out);
+    }
+}
+
+fn recorded_app() -> (bevy_app::App, Entity) {
+    let mut app = bevy_app::App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    let root = app
+        .world_mut()
+        .spawn((
+            SptsDioxusRootComponent::new(labelled_list),
+            SptsDioxusRecordMutations,
+        ))
+        .id();
+    for count in [2, 3, 1] {
+        app.insert_resource(Count(count));
+        app.update();
+    }
+    (app, root)
+}
+
+fn take_log(app: &mut bevy_app::App, root: Entity) -> SptsDioxusMutationLog {
+    app.world_mut()
+        .non_send_resource_mut::<SptsDioxusContext<SptsDioxusAdapter>>()
+        .take_mutation_log(root)
+        .unwrap()
+}
+
+#[test]
+fn replayed_log_reproduces_the_rendered_entities() {
+    let (mut app, root) = recorded_app();
+    let mut log = take_log(&mut app, root);
+    assert!(log
+        .mutations
+        .iter()
+        .any(|mutation| matches!(mutation, SptsDioxusMutation::RemoveNode { .. })));
+
+    let mut expected = String::new();
+    hierarchy(app.world(), root, 0, &mut expected);
+    assert_eq!(expected, "node\n  node\n    \"item 1\"\n");
+
+    let mut world = World::new();
+    let replayed_root = log.replay::<SptsDioxusAdapter>(&mut world);
+    let mut replayed = String::new();
+    hierarchy(&world, replayed_root, 0, &mut replayed);
+    assert_eq!(replayed, expected);
+
+    let mut values: Vec<i32> = world.query::<&Value>().iter(&world).map(|v| v.0).collect();
+    values.sort();
+    assert_eq!(values, rendered_values(&mut app));
+    assert_eq!(world.query::<&SptsDioxusTabIndex>().iter(&world).count(), 1);
+
+    // Taking the log starts a new one, which replays after the first.
+    app.insert_resource(Count(2));
+    app.update();
+    let next_log = take_log(&mut app, root);
+    assert!(!next_log.mutations.is_empty());
+    assert!(!next_log
+        .mutations
+        .iter()
+        .any(|mutation| matches!(mutation, SptsDioxusMutation::RemoveNode { .. })));
+
+    log.append(&next_log);
+    let mut expected = String::new();
+    hierarchy(app.world(), root, 0, &mut expected);
+    let mut world = World::new();
+    let replayed_root = log.replay::<SptsDioxusAdapter>(&mut world);
+    let mut replayed = String::new();
+    hierarchy(&world, replayed_root, 0, &mut replayed);
+    assert_eq!(replayed, expected);
+}
+
+#[test]
+fn roots_without_the_marker_are_not_recorded() {
+    let mut app = common::app_with_root(labelled_list);
+    app.insert_resource(Count(1));
+    app.update();
+    let root = app
+        .world_mut()
+        .query_filtered::<Entity, With<SptsDioxusRootComponent>>()
+        .single(app.world());
+    assert!(app
+        .world_mut()
+        .non_send_resource_mut::<SptsDioxusContext<SptsDioxusAdapter>>()
+        .take_mutation_log(root)
+        .is_none());
+}
+
+#[test]
+fn removing_the_marker_stops_recording() {
+    let (mut app, root) = recorded_app();
+    let mut expected = String::new();
+    hierarchy(app.world(), root, 0, &mut expected);
+
+    app.world_mut()
+        .entity_mut(root)
+        .remove::<SptsDioxusRecordMutations>();
+    app.insert_resource(Count(2));
+    app.update();
+    assert_eq!(rendered_values(&mut app), vec![0, 1, 2]);
+
+    // The log ends where the marker was removed.
+    let log = take_log(&mut app, root);
+    let mut world = World::new();
+    let replayed_root = log.replay::<SptsDioxusAdapter>(&mut world);
+    let mut replayed = String::new();
+    hierarchy(&world, replayed_root, 0, &mut replayed);
+    assert_eq!(replayed, expected);
+}
+
+#[test]
+fn malformed_logs_report_errors() {
+    let mut log = SptsDioxusMutationLog::default();
+    // Only the root is on the stack.
+    log.append_children(ElementId(0), 2);
+    // The placeholder pushed for the unknown element is despawned along with it.
+    log.push_root(ElementId(1));
+    log.remove_node(ElementId(1));
+    let template = Template {
+        roots: &[TemplateNode::Text { text: "text" }],
+        node_paths: &[],
+        attr_paths: &[],
+    };
+    log.load_template(template, 1, ElementId(2));
+
+    let mut world = World::new();
+    world.init_resource::<Events<SptsDioxusError>>();
+    log.replay::<SptsDioxusAdapter>(&mut world);
+    let errors: Vec<_> = world
+        .resource::<Events<SptsDioxusError>>()
+        .iter_current_update_events()
+        .map(|error| error.kind.clone())
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            SptsDioxusErrorKind::StackUnderflow {
+                expected: 2,
+                found: 1
+            },
+            SptsDioxusErrorKind::UnknownElementId(ElementId(1)),
+            SptsDioxusErrorKind::MissingTemplateRoot { index: 1, roots: 1 },
+        ]
+    );
+    // Only the placeholder standing in for the template is left.
+    let placeholders = world
+        .query_filtered::<(), With<SptsDioxusPlaceholder>>()
+        .iter(&world)
+        .count();
+    assert_eq!(placeholders, 1);
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn log_round_trips_through_ron() {
+    let (mut app, root) = recorded_app();
+    let log = take_log(&mut app, root);
+    let serialized = ron::to_string(&log).unwrap();
+    let deserialized: SptsDioxusMutationLog = ron::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.templates.len(), log.templates.len());
+    assert_eq!(deserialized.mutations.len(), log.mutations.len());
+
+    let mut expected = String::new();
+    hierarchy(app.world(), root, 0, &mut expected);
+    let mut world = World::new();
+    let replayed_root = deserialized.replay::<SptsDioxusAdapter>(&mut world);
+    let mut replayed = String::new();
+    hierarchy(&world, replayed_root, 0, &mut replayed);
+    assert_eq!(replayed, expected);
+    // Components passed with `WA(..)` can't be serialized, the elements keep their defaults.
+    let values: Vec<i32> = world.query::<&Value>().iter(&world).map(|v| v.0).collect();
+    assert_eq!(values, vec![0, 0]);
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn deserialized_logs_reuse_their_templates_when_appended_to() {
+    let (mut app, root) = recorded_app();
+    let log = take_log(&mut app, root);
+    let mut deserialized: SptsDioxusMutationLog =
+        ron::from_str(&ron::to_string(&log).unwrap()).unwrap();
+
+    // The new item is loaded from a template the first log already has.
+    app.insert_resource(Count(2));
+    app.update();
+    let next_log = take_log(&mut app, root);
+    assert!(next_log
+        .mutations
+        .iter()
+        .any(|mutation| matches!(mutation, SptsDioxusMutation::LoadTemplate { .. })));
+    deserialized.append(&next_log);
+    assert_eq!(deserialized.templates.len(), log.templates.len());
+
+    let mut expected = String::new();
+    hierarchy(app.world(), root, 0, &mut expected);
+    let mut world = World::new();
+    let replayed_root = deserialized.replay::<SptsDioxusAdapter>(&mut world);
+    let mut replayed = String::new();
+    hierarchy(&world, replayed_root, 0, &mut replayed);
+    assert_eq!(replayed, expected);
+}
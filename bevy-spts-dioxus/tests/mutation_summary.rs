@@ -0,0 +1,54 @@
+use bevy_app::App;
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, labelled_list, Count};
+
+fn summary(app: &mut App) -> SptsDioxusMutationSummary {
+    let world = app.world_mut();
+    *world.query::<&SptsDioxusMutationSummary>().single(world)
+}
+
+#[test]
+fn summary_counts_the_mutations_of_the_last_frame() {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    app.world_mut().spawn((
+        SptsDioxusRootComponent::new(labelled_list),
+        SptsDioxusMutationSummary::default(),
+    ));
+    app.insert_resource(Count(2));
+    app.update();
+
+    // The list and two items with their text, the `tabindex` built-in isn't counted.
+    assert_eq!(
+        summary(&mut app),
+        SptsDioxusMutationSummary {
+            created: 5,
+            removed: 0,
+            updated: 3,
+        }
+    );
+
+    app.insert_resource(Count(1));
+    app.update();
+    assert_eq!(
+        summary(&mut app),
+        SptsDioxusMutationSummary {
+            created: 0,
+            removed: 1,
+            updated: 0,
+        }
+    );
+
+    app.insert_resource(Count(3));
+    app.update();
+    assert_eq!(
+        summary(&mut app),
+        SptsDioxusMutationSummary {
+            created: 4,
+            removed: 0,
+            updated: 2,
+        }
+    );
+}
@@ -0,0 +1,77 @@
+use bevy_ecs::{observer::Observer, prelude::*};
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, app_with_root, Value};
+
+#[derive(Event)]
+struct Ping;
+
+#[derive(Resource, Default)]
+struct Pings(Vec<i32>);
+
+/// Which observer the node uses, `None` unmounts it.
+#[derive(Resource, Clone)]
+struct Mode(Option<i32>);
+
+fn pings_with(id: i32) -> SptsDioxusObserver {
+    SptsDioxusObserver::new(move |_: Trigger<Ping>, mut pings: ResMut<Pings>| pings.0.push(id))
+}
+
+#[component]
+fn observes() -> Element {
+    let mode = Hooks::use_bevy_resource::<Mode>().0;
+    let observers = use_hook(|| [pings_with(1), pings_with(2)]);
+    let node = match mode {
+        Some(mode) => {
+            let observer = observers[mode as usize - 1].clone();
+            rsx! { node { value: WA(Value(mode)), observe: observer } }
+        }
+        None => rsx! {},
+    };
+    rsx! {
+        {node}
+    }
+}
+
+fn ping(app: &mut bevy_app::App) {
+    let world = app.world_mut();
+    if let Ok(entity) = world.query_filtered::<Entity, With<Value>>().get_single(world) {
+        world.trigger_targets(Ping, entity);
+    }
+    app.update();
+}
+
+fn observer_count(app: &mut bevy_app::App) -> usize {
+    let world = app.world_mut();
+    world.query::<&Observer>().iter(world).count()
+}
+
+#[test]
+fn observe_attribute_attaches_and_removes_observers() {
+    let mut app = app_with_root(observes);
+    app.init_resource::<Pings>();
+    app.insert_resource(Mode(Some(1)));
+    app.update();
+    assert_eq!(observer_count(&mut app), 1);
+    ping(&mut app);
+    assert_eq!(app.world().resource::<Pings>().0, vec![1]);
+
+    // Swapping the observer despawns the previous one.
+    app.insert_resource(Mode(Some(2)));
+    app.update();
+    assert_eq!(observer_count(&mut app), 1);
+    ping(&mut app);
+    assert_eq!(app.world().resource::<Pings>().0, vec![1, 2]);
+
+    // Re-rendering with the same observer keeps it.
+    app.insert_resource(Mode(Some(2)));
+    app.update();
+    ping(&mut app);
+    assert_eq!(app.world().resource::<Pings>().0, vec![1, 2, 2]);
+
+    app.insert_resource(Mode(None));
+    app.update();
+    app.update();
+    assert_eq!(observer_count(&mut app), 0);
+}
@@ -0,0 +1,181 @@
+//! Pointer events from `bevy_picking`, driven headlessly by sending synthetic hits and input.
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
+use bevy_render::camera::{ManualTextureViewHandle, NormalizedRenderTarget};
+use bevy_spts_dioxus::{
+    bevy_picking::{
+        backend::{HitData, PointerHits},
+        events::{Click, Out, Over, Pointer},
+        pointer::{
+            Location, PointerAction, PointerButton, PointerId, PointerInput, PointerLocation,
+            PressDirection,
+        },
+        InteractionPlugin, PickingPlugin,
+    },
+    *,
+};
+
+mod common;
+use common::Value;
+
+#[bevy_spts_dioxus]
+pub mod adapter {
+    use super::common::Value;
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+    use bevy_spts_dioxus::{
+        bevy_picking::events::{DragStart, Pointer},
+        *,
+    };
+
+    pub mod dioxus_elements {
+        use bevy_spts_dioxus::SptsDioxusElement;
+
+        #[define_element]
+        pub struct button {
+            #[component]
+            value: Value,
+            // The pointer events are built in, declaring one is still allowed.
+            #[event]
+            ondragstart: Pointer<DragStart>,
+        }
+        impl SptsDioxusElement for button {}
+    }
+}
+use adapter::*;
+
+#[derive(Resource, Default)]
+struct Log(Vec<String>);
+
+#[component]
+fn root() -> Element {
+    let log = Hooks::use_resource_mut::<Log>();
+    let push = move |entry: String| log.write(move |log| log.0.push(entry));
+
+    rsx! {
+        button {
+            value: WA(Value(0)),
+            onclick: {
+                let push = push.clone();
+                move |e: DioxusEvent<Pointer<Click>>| push(format!("click {}", e.target))
+            },
+            onpointerover: {
+                let push = push.clone();
+                move |e: DioxusEvent<Pointer<Over>>| push(format!("over {}", e.target))
+            },
+            onpointerout: {
+                let push = push.clone();
+                move |e: DioxusEvent<Pointer<Out>>| push(format!("out {}", e.target))
+            },
+            ondragstart: move |_| push("dragstart".to_string()),
+            // Hits land on the children, the events bubble up to the parent's listeners.
+            button { value: WA(Value(1)) }
+            button { value: WA(Value(2)) }
+        }
+    }
+}
+
+fn location(x: f32) -> Location {
+    Location {
+        target: NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0)),
+        position: Vec2::new(x, 0.),
+    }
+}
+
+fn hit(app: &mut App, entity: Option<Entity>) {
+    let picks = entity
+        .map(|entity| (entity, HitData::new(Entity::PLACEHOLDER, 0., None, None)))
+        .into_iter()
+        .collect();
+    app.world_mut()
+        .send_event(PointerHits::new(PointerId::Mouse, picks, 0.));
+}
+
+fn input(app: &mut App, action: PointerAction, x: f32) {
+    app.world_mut()
+        .send_event(PointerInput::new(PointerId::Mouse, location(x), action));
+}
+
+fn press(direction: PressDirection) -> PointerAction {
+    PointerAction::Pressed {
+        direction,
+        button: PointerButton::Primary,
+    }
+}
+
+fn take_log(app: &mut App) -> Vec<String> {
+    std::mem::take(&mut app.world_mut().resource_mut::<Log>().0)
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((PickingPlugin::default(), InteractionPlugin));
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    app.world_mut().spawn(SptsDioxusRootComponent::new(root));
+    app.init_resource::<Log>();
+    app.world_mut()
+        .spawn((PointerId::Mouse, PointerLocation::new(location(0.))));
+    app.update();
+    app
+}
+
+fn find_value(app: &mut App, value: i32) -> Entity {
+    let world = app.world_mut();
+    world
+        .query::<(Entity, &Value)>()
+        .iter(world)
+        .find_map(|(entity, v)| (v.0 == value).then_some(entity))
+        .unwrap()
+}
+
+#[test]
+fn pointer_events_bubble_to_dioxus_listeners() {
+    let mut app = app();
+    let child = find_value(&mut app, 1);
+
+    hit(&mut app, Some(child));
+    app.update();
+    assert_eq!(take_log(&mut app), vec![format!("over {child}")]);
+
+    hit(&mut app, Some(child));
+    input(&mut app, press(PressDirection::Down), 0.);
+    app.update();
+    hit(&mut app, Some(child));
+    input(&mut app, press(PressDirection::Up), 0.);
+    app.update();
+    assert_eq!(take_log(&mut app), vec![format!("click {child}")]);
+
+    hit(&mut app, Some(child));
+    input(&mut app, press(PressDirection::Down), 0.);
+    app.update();
+    hit(&mut app, Some(child));
+    input(&mut app, PointerAction::Moved { delta: Vec2::X }, 1.);
+    app.update();
+    assert_eq!(take_log(&mut app), vec!["dragstart"]);
+
+    hit(&mut app, None);
+    app.update();
+    assert_eq!(take_log(&mut app), vec![format!("out {child}")]);
+}
+
+#[test]
+fn pointer_over_and_out_fire_when_moving_between_children() {
+    let mut app = app();
+    let first = find_value(&mut app, 1);
+    let second = find_value(&mut app, 2);
+
+    hit(&mut app, Some(first));
+    app.update();
+    assert_eq!(take_log(&mut app), vec![format!("over {first}")]);
+
+    // The parent is still hovered, yet its listeners see the pointer leave one child for the
+    // other, like `mouseover` / `mouseout`.  `bevy_picking` doesn't order the two.
+    hit(&mut app, Some(second));
+    app.update();
+    let mut log = take_log(&mut app);
+    log.sort();
+    assert_eq!(log, vec![format!("out {first}"), format!("over {second}")]);
+}
@@ -0,0 +1,90 @@
+use bevy_ecs::prelude::*;
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{Count, Value};
+
+fn placeholders(world: &mut World) -> Vec<Entity> {
+    world
+        .query_filtered::<Entity, With<SptsDioxusPlaceholder>>()
+        .iter(world)
+        .collect()
+}
+
+mod default_placeholder {
+    use super::{common::app_with_root, common::list, placeholders, Count};
+    use bevy_spts_dioxus::*;
+
+    #[test]
+    fn placeholders_only_have_the_marker() {
+        let mut app = app_with_root(list);
+        app.insert_resource(Count(0));
+        app.update();
+
+        let found = placeholders(app.world_mut());
+        assert_eq!(found.len(), 1);
+        let placeholder = app.world().entity(found[0]);
+        assert_eq!(
+            placeholder.get::<SptsDioxusManaged>().unwrap().tag,
+            SptsDioxusManaged::PLACEHOLDER
+        );
+        // The marker, `Parent` and `SptsDioxusManaged`, nothing that would be propagated to.
+        assert_eq!(app.world().inspect_entity(found[0]).count(), 3);
+
+        app.insert_resource(Count(1));
+        app.update();
+        assert!(placeholders(app.world_mut()).is_empty());
+    }
+}
+
+/// Gives placeholders a `Value` so the test can find them.
+fn spawn_marked_placeholder(world: &mut World) -> Entity {
+    world.spawn((SptsDioxusPlaceholder, Value(-1))).id()
+}
+
+#[bevy_spts_dioxus(placeholder = spawn_marked_placeholder)]
+pub mod adapter {
+    use super::{spawn_marked_placeholder, Value};
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+    use bevy_spts_dioxus::*;
+
+    pub mod dioxus_elements {
+        use bevy_spts_dioxus::SptsDioxusElement;
+
+        #[define_element]
+        pub struct node {
+            #[component]
+            value: Value,
+        }
+        impl SptsDioxusElement for node {}
+    }
+}
+use adapter::*;
+
+#[component]
+fn list() -> Element {
+    let count = Hooks::use_bevy_resource::<Count>().0;
+    rsx! {
+        node {
+            for i in 1..=count {
+                node { key: "{i}", value: WA(Value(i)) }
+            }
+        }
+    }
+}
+
+#[test]
+fn placeholder_option_spawns_placeholders() {
+    let mut app = bevy_app::App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    app.world_mut().spawn(SptsDioxusRootComponent::new(list));
+    app.insert_resource(Count(0));
+    app.update();
+
+    let world = app.world_mut();
+    let values: Vec<i32> = world.query::<&Value>().iter(world).map(|v| v.0).collect();
+    assert!(values.contains(&-1), "{values:?}");
+    assert_eq!(placeholders(world).len(), 1);
+}
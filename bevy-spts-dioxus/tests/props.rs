@@ -0,0 +1,122 @@
+use std::cell::Cell;
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, Value};
+
+thread_local! {
+    static RENDERS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[component]
+fn Labelled(value: i32) -> Element {
+    RENDERS.set(RENDERS.get() + 1);
+    rsx! {
+        node { value: WA(Value(value)) }
+    }
+}
+
+#[component]
+fn Doubled(half: u8) -> Element {
+    rsx! {
+        node { value: WA(Value(half as i32 * 2)) }
+    }
+}
+
+fn app(value: i32) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    let root = app
+        .world_mut()
+        .spawn(SptsDioxusRootComponent::with_props(
+            Labelled,
+            LabelledProps { value },
+        ))
+        .id();
+    app.update();
+    (app, root)
+}
+
+fn values(app: &mut App) -> Vec<(Entity, i32)> {
+    let world = app.world_mut();
+    world
+        .query::<(Entity, &Value)>()
+        .iter(world)
+        .map(|(entity, value)| (entity, value.0))
+        .collect()
+}
+
+#[test]
+fn roots_render_with_their_props() {
+    RENDERS.set(0);
+    let (mut app, _) = app(4);
+    assert_eq!(
+        values(&mut app).iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+        vec![4]
+    );
+    assert_eq!(RENDERS.get(), 1);
+}
+
+#[test]
+fn new_props_re_render_without_a_teardown() {
+    RENDERS.set(0);
+    let (mut app, root) = app(1);
+    let [(entity, 1)] = values(&mut app)[..] else {
+        panic!("expected one node");
+    };
+
+    app.world_mut()
+        .entity_mut(root)
+        .insert(SptsDioxusRootComponent::with_props(
+            Labelled,
+            LabelledProps { value: 2 },
+        ));
+    app.update();
+    assert_eq!(values(&mut app), vec![(entity, 2)]);
+    assert_eq!(RENDERS.get(), 2);
+}
+
+#[test]
+fn equal_props_are_ignored() {
+    RENDERS.set(0);
+    let (mut app, root) = app(1);
+
+    // Marks the component as changed without changing the props.
+    app.world_mut()
+        .get_mut::<SptsDioxusRootComponent>(root)
+        .unwrap()
+        .set_changed();
+    app.update();
+    app.world_mut()
+        .entity_mut(root)
+        .insert(SptsDioxusRootComponent::with_props(
+            Labelled,
+            LabelledProps { value: 1 },
+        ));
+    app.update();
+    assert_eq!(RENDERS.get(), 1);
+}
+
+#[test]
+fn other_props_types_tear_down_the_root() {
+    let (mut app, root) = app(1);
+    let [(entity, 1)] = values(&mut app)[..] else {
+        panic!("expected one node");
+    };
+
+    app.world_mut()
+        .entity_mut(root)
+        .insert(SptsDioxusRootComponent::with_props(
+            Doubled,
+            DoubledProps { half: 3 },
+        ));
+    app.update();
+    let [(new_entity, 6)] = values(&mut app)[..] else {
+        panic!("expected the doubled node");
+    };
+    assert_ne!(new_entity, entity);
+    assert!(app.world().get_entity(entity).is_err());
+}
@@ -0,0 +1,142 @@
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::Value;
+
+#[derive(Resource, Clone)]
+struct Show(bool);
+
+#[derive(Component, Default, Clone, PartialEq, Debug)]
+struct Size(i32);
+
+#[derive(Component, Clone, PartialEq, Debug)]
+struct Label(String);
+
+/// Number of entities `Value` was added to.
+#[derive(Resource, Default)]
+struct ValuesAdded(usize);
+
+#[bevy_spts_dioxus]
+pub mod adapter {
+    use super::{Label, Size, Value};
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+    use bevy_spts_dioxus::*;
+    use dioxus_core::AttributeValue;
+
+    #[define_attr(remove = clear_label)]
+    pub fn label_attr(world: &mut World, entity: Entity, value: &AttributeValue) {
+        if let Some(label) = value.as_string() {
+            world.entity_mut(entity).insert(Label(label.clone()));
+        }
+    }
+
+    pub fn clear_label(world: &mut World, entity: Entity) {
+        world
+            .entity_mut(entity)
+            .insert(Label("cleared".to_string()));
+    }
+
+    pub mod dioxus_elements {
+        use super::Value;
+        use bevy_ecs::world::{EntityWorldMut, World};
+        use bevy_spts_dioxus::SptsDioxusElement;
+
+        #[define_element]
+        pub struct node {
+            #[component]
+            value: Value,
+            #[component(remove)]
+            size: Size,
+            #[attr]
+            label: label_attr,
+        }
+        impl SptsDioxusElement for node {
+            fn spawn(world: &mut World) -> EntityWorldMut<'_> {
+                world.spawn(Value(7))
+            }
+        }
+    }
+}
+use adapter::*;
+
+#[component]
+fn root() -> Element {
+    let show = Hooks::use_bevy_resource::<Show>().0;
+    rsx! {
+        node {
+            value: show.then_some(WA(Value(1))),
+            size: show.then_some(WA(Size(2))),
+            label: show.then_some("shown"),
+        }
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default());
+    app.world_mut().spawn(SptsDioxusRootComponent::new(root));
+    app.init_resource::<ValuesAdded>();
+    app.add_observer(|_: Trigger<OnAdd, Value>, mut added: ResMut<ValuesAdded>| added.0 += 1);
+    app.insert_resource(Show(true));
+    app.update();
+    app
+}
+
+fn values(app: &mut App) -> Vec<i32> {
+    let world = app.world_mut();
+    world.query::<&Value>().iter(world).map(|v| v.0).collect()
+}
+
+fn sizes(app: &mut App) -> Vec<Size> {
+    let world = app.world_mut();
+    world.query::<&Size>().iter(world).cloned().collect()
+}
+
+fn labels(app: &mut App) -> Vec<String> {
+    let world = app.world_mut();
+    world
+        .query::<&Label>()
+        .iter(world)
+        .map(|l| l.0.clone())
+        .collect()
+}
+
+#[test]
+fn removed_components_reset_to_their_spawned_value() {
+    let mut app = app();
+    assert_eq!(values(&mut app), vec![1]);
+
+    app.insert_resource(Show(false));
+    app.update();
+    assert_eq!(values(&mut app), vec![7]);
+    // Only the element's entity got a `Value`, the reset value was recorded when it spawned.
+    assert_eq!(app.world().resource::<ValuesAdded>().0, 1);
+}
+
+#[test]
+fn removable_components_are_removed() {
+    let mut app = app();
+    assert_eq!(sizes(&mut app), vec![Size(2)]);
+
+    app.insert_resource(Show(false));
+    app.update();
+    assert_eq!(sizes(&mut app), vec![]);
+
+    app.insert_resource(Show(true));
+    app.update();
+    assert_eq!(sizes(&mut app), vec![Size(2)]);
+}
+
+#[test]
+fn removed_attributes_call_the_remove_fn() {
+    let mut app = app();
+    assert_eq!(labels(&mut app), vec!["shown"]);
+
+    app.insert_resource(Show(false));
+    app.update();
+    assert_eq!(labels(&mut app), vec!["cleared"]);
+}
@@ -0,0 +1,84 @@
+use bevy_app::{App, PostUpdate};
+use bevy_ecs::{prelude::*, schedule::ScheduleLabel};
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, list, rendered_values, Count, Value};
+
+#[derive(Resource, Default)]
+struct SeenValues(usize);
+
+fn count_values(values: Query<&Value>, mut seen: ResMut<SeenValues>) {
+    seen.0 = values.iter().count();
+}
+
+#[test]
+fn systems_after_apply_mutations_see_the_rendered_entities() {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default().in_schedule(PostUpdate))
+        .init_resource::<SeenValues>()
+        .insert_resource(Count(2))
+        .add_systems(
+            PostUpdate,
+            count_values.after(SptsDioxusSet::ApplyMutations),
+        );
+    app.world_mut().spawn(SptsDioxusRootComponent::new(list));
+    app.update();
+    // The outer node and the two items.
+    assert_eq!(app.world().resource::<SeenValues>().0, 3);
+}
+
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct UiSchedule;
+
+#[test]
+fn custom_schedule_can_run_more_than_once_a_frame() {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default().in_schedule(UiSchedule))
+        .insert_resource(Count(1));
+    app.world_mut().spawn(SptsDioxusRootComponent::new(list));
+
+    // Nothing renders until the schedule runs.
+    app.update();
+    assert!(rendered_values(&mut app).is_empty());
+
+    app.world_mut().run_schedule(UiSchedule);
+    assert_eq!(rendered_values(&mut app), vec![0, 1]);
+    app.world_mut().resource_mut::<Count>().0 = 2;
+    app.world_mut().run_schedule(UiSchedule);
+    assert_eq!(rendered_values(&mut app), vec![0, 1, 2]);
+}
+
+#[derive(Resource, Default)]
+struct FocusCount(usize);
+
+#[component]
+fn focusable() -> Element {
+    let focus_count = Hooks::use_resource_mut::<FocusCount>();
+    rsx! {
+        node {
+            value: WA(Value(1)),
+            tabindex: 0,
+            onfocus: move |_| focus_count.write(|focus_count| focus_count.0 += 1),
+        }
+    }
+}
+
+#[test]
+fn focus_events_are_sent_in_the_custom_schedule() {
+    let mut app = App::new();
+    app.add_plugins(SptsDioxusPlugin::<SptsDioxusAdapter>::default().in_schedule(UiSchedule))
+        .init_resource::<FocusCount>();
+    app.world_mut()
+        .spawn(SptsDioxusRootComponent::new(focusable));
+    app.world_mut().run_schedule(UiSchedule);
+
+    app.world_mut()
+        .resource_mut::<SptsDioxusFocus>()
+        .focus_next();
+    // Outside of the schedule nothing is sent.
+    app.update();
+    assert_eq!(app.world().resource::<FocusCount>().0, 0);
+    app.world_mut().run_schedule(UiSchedule);
+    assert_eq!(app.world().resource::<FocusCount>().0, 1);
+}
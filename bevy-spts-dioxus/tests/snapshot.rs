@@ -0,0 +1,83 @@
+use bevy_ecs::prelude::*;
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, app_with_root, labelled_list, Count, Value};
+
+fn root(app: &mut bevy_app::App) -> Entity {
+    app.world_mut()
+        .query_filtered::<Entity, With<SptsDioxusRootComponent>>()
+        .single(app.world())
+}
+
+fn snapshot(app: &mut bevy_app::App) -> String {
+    let root = root(app);
+    snapshot_root::<SptsDioxusAdapter>(app.world(), root).unwrap()
+}
+
+const ONE_ITEM: &str = r#"(
+  tag: "node",
+  element: 1,
+  attributes: {
+    "tabindex": 0,
+    "value": Any,
+  },
+  components: {
+    "Value": Value(0),
+  },
+  children: [
+    (
+      tag: "node",
+      element: 2,
+      attributes: {
+        "value": Any,
+      },
+      components: {
+        "Value": Value(1),
+      },
+      children: [
+        (
+          text: "item 1",
+          element: 3,
+        ),
+      ],
+    ),
+  ],
+),
+"#;
+
+#[test]
+fn snapshot_contains_tags_attributes_and_components() {
+    let mut app = app_with_root(labelled_list);
+    let root = root(&mut app);
+    app.world_mut()
+        .entity_mut(root)
+        .insert(SptsDioxusSnapshotAttributes);
+    app.world_mut()
+        .resource_mut::<SptsDioxusSnapshotComponents>()
+        .add::<Value>();
+    app.insert_resource(Count(1));
+    app.update();
+    assert_eq!(snapshot(&mut app), ONE_ITEM);
+
+    // Stable between frames when nothing changed.
+    app.update();
+    assert_eq!(snapshot(&mut app), ONE_ITEM);
+
+    // The removed items' attributes are forgotten, dioxus holds their place with a placeholder.
+    app.insert_resource(Count(0));
+    app.update();
+    let snapshot = snapshot(&mut app);
+    assert!(!snapshot.contains("item 1"), "{snapshot}");
+    assert!(snapshot.contains("placeholder: true"), "{snapshot}");
+}
+
+#[test]
+fn attributes_are_only_kept_with_the_marker() {
+    let mut app = app_with_root(labelled_list);
+    app.insert_resource(Count(1));
+    app.update();
+    let snapshot = snapshot(&mut app);
+    assert!(snapshot.contains("item 1"), "{snapshot}");
+    assert!(!snapshot.contains("attributes"), "{snapshot}");
+}
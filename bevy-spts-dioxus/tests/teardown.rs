@@ -0,0 +1,96 @@
+use std::cell::Cell;
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::Children;
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, app_with_root, Value};
+
+thread_local! {
+    static DROPPED: Cell<usize> = const { Cell::new(0) };
+}
+
+#[component]
+fn first() -> Element {
+    use_drop(|| DROPPED.set(DROPPED.get() + 1));
+    rsx! {
+        node {
+            value: WA(Value(1)),
+            node { value: WA(Value(2)) }
+        }
+    }
+}
+
+#[component]
+fn second() -> Element {
+    rsx! {
+        node { value: WA(Value(3)) }
+    }
+}
+
+fn root(app: &mut App) -> Entity {
+    app.world_mut()
+        .query_filtered::<Entity, With<SptsDioxusRootComponent>>()
+        .single(app.world())
+}
+
+fn values(app: &mut App) -> Vec<(Entity, i32)> {
+    let world = app.world_mut();
+    world
+        .query::<(Entity, &Value)>()
+        .iter(world)
+        .map(|(entity, value)| (entity, value.0))
+        .collect()
+}
+
+#[test]
+fn swapping_the_component_rebuilds_the_root() {
+    DROPPED.set(0);
+    let mut app = app_with_root(first);
+    app.update();
+    let old_entities: Vec<Entity> = values(&mut app).into_iter().map(|(e, _)| e).collect();
+    assert_eq!(old_entities.len(), 2);
+
+    let root = root(&mut app);
+    app.world_mut()
+        .entity_mut(root)
+        .insert(SptsDioxusRootComponent::new(second));
+    app.update();
+
+    assert_eq!(DROPPED.get(), 1);
+    for entity in old_entities {
+        assert!(app.world().get_entity(entity).is_err());
+    }
+    let new_values = values(&mut app);
+    assert_eq!(
+        new_values.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+        vec![3]
+    );
+    assert_eq!(
+        app.world().get::<Children>(root).unwrap().to_vec(),
+        vec![new_values[0].0]
+    );
+}
+
+#[test]
+fn removing_the_component_despawns_everything_but_the_root() {
+    DROPPED.set(0);
+    let mut app = app_with_root(first);
+    app.update();
+    let root = root(&mut app);
+
+    app.world_mut()
+        .entity_mut(root)
+        .remove::<SptsDioxusRootComponent>();
+    app.update();
+
+    assert_eq!(DROPPED.get(), 1);
+    assert!(app.world().get_entity(root).is_ok());
+    assert!(values(&mut app).is_empty());
+    assert!(app
+        .world()
+        .get::<Children>(root)
+        .is_none_or(|children| children.is_empty()));
+}
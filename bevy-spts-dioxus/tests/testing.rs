@@ -0,0 +1,72 @@
+use std::cell::Cell;
+
+use bevy_spts_dioxus::*;
+
+mod common;
+use common::{adapter::*, Count, Value};
+
+thread_local! {
+    static MARKED: Cell<Option<EntitySignal>> = const { Cell::new(None) };
+}
+
+#[component]
+fn list() -> Element {
+    let marked = Hooks::use_entity();
+    MARKED.set(Some(marked));
+    let count = Hooks::use_bevy_resource::<Count>().0;
+    rsx! {
+        node {
+            value: WA(Value(0)),
+            for i in 1..=count {
+                node { key: "{i}", value: WA(Value(i)) }
+            }
+            node {
+                entity: marked,
+                value: WA(Value(10)),
+                "marked"
+            }
+        }
+    }
+}
+
+#[test]
+fn test_app_finds_entities_and_asserts_hierarchy() {
+    let mut test_app = SptsDioxusTestApp::<SptsDioxusAdapter>::new(list);
+    test_app.app_mut().insert_resource(Count(2));
+    test_app.step();
+
+    assert_eq!(test_app.find_by_tag("node").len(), 4);
+    test_app.assert_hierarchy(
+        "
+        node
+          node
+          node
+          node
+            \"marked\"
+        ",
+    );
+
+    let marked = test_app.find_by_entity_signal(MARKED.get().unwrap());
+    assert!(marked.is_some());
+    assert_eq!(
+        test_app.find_by_component(|value: &Value| value.0 == 10),
+        Vec::from_iter(marked)
+    );
+    let values: Vec<i32> = test_app
+        .find_by_tag("node")
+        .into_iter()
+        .map(|entity| test_app.world().get::<Value>(entity).unwrap().0)
+        .collect();
+    assert_eq!(values, vec![0, 1, 2, 10]);
+
+    // The placeholder dioxus spawns for the empty list isn't part of the hierarchy.
+    test_app.world_mut().resource_mut::<Count>().0 = 0;
+    test_app.step_frames(2);
+    test_app.assert_hierarchy(
+        "
+        node
+          node
+            \"marked\"
+        ",
+    );
+}
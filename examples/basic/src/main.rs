@@ -21,6 +21,17 @@ pub mod my_adapter {
         }
     }
 
+    // Bevy events that can be listened to with `#[event]` fields.
+    #[derive(Event, Clone, Debug)]
+    pub struct PressEvent {
+        pub entity: Entity,
+    }
+    impl SptsDioxusElementEvent for PressEvent {
+        fn target(&self) -> Option<Entity> {
+            Some(self.entity)
+        }
+    }
+
     // Implement custom attributes
     #[define_attr()]
     pub fn is_visible_attr(world: &mut World, entity: Entity, value: &AttributeValue) {
@@ -49,6 +60,10 @@ pub mod my_adapter {
             // Can compose your attributes across multiple elements.
             #[attr]
             is_visible: is_visible_attr,
+
+            // Listen to bevy events with `onpress: move |e| { ... }`
+            #[event]
+            onpress: PressEvent,
         }
         impl SptsDioxusElement for spatial {}
     }
@@ -60,6 +75,7 @@ use my_adapter::*;
 pub fn root_component() -> Element {
     let outer_entity = Hooks::use_entity();
     let inner_entity = Hooks::use_entity();
+    let mut press_count = use_signal(|| 0);
 
     use_effect(move || {
         println!("outer_entity: {outer_entity:?}, inner_entity: {inner_entity:?}.");
@@ -67,7 +83,12 @@ pub fn root_component() -> Element {
     rsx! {
         spatial {
             entity: outer_entity,
-            name: "Outer",
+            name: "Outer (pressed {press_count})",
+            // Events bubble up from the inner element.
+            onpress: move |e: DioxusEvent<PressEvent>| {
+                println!("Pressed {:?}", e.data().entity);
+                press_count += 1;
+            },
             // Pass your values to your attributes
             is_visible: true,
             // Reactively set whole attributes (must be wrapped with the WA, WrappedAttribute, struct)
@@ -87,10 +108,24 @@ pub fn spawn_root(mut commands: Commands) {
     commands.spawn((
         Transform::default(),
         Visibility::default(),
-        SptsDioxusRootComponent(root_component),
+        SptsDioxusRootComponent::new(root_component),
     ));
 }
 
+pub fn press_inner(
+    keys: Res<ButtonInput<KeyCode>>,
+    names: Query<(Entity, &Name)>,
+    mut writer: EventWriter<PressEvent>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        for (entity, name) in &names {
+            if name.as_str() == "Inner" {
+                writer.send(PressEvent { entity });
+            }
+        }
+    }
+}
+
 pub fn main() {
     let mut app = App::new();
     app.add_plugins(DefaultPlugins);
@@ -98,5 +133,6 @@ pub fn main() {
     app.add_plugins(WorldInspectorPlugin::new());
     // Spawn your root bundle.
     app.add_systems(Startup, spawn_root);
+    app.add_systems(Update, press_inner);
     app.run();
 }
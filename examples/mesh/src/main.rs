@@ -186,7 +186,7 @@ mod my_adapter {
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct State {
     pressed_count: usize,
 }
@@ -223,7 +223,7 @@ pub fn root() -> Element {
             position_x: (state.pressed_count as f64) * 0.01,
             position_y: 1.0,
             position_z: 0.5,
-            visibility: WA(if state.pressed_count % 2 == 0 { Visibility::Visible } else { Visibility::Hidden }),
+            visibility: WA(if state.pressed_count.is_multiple_of(2) { Visibility::Visible } else { Visibility::Hidden }),
 
             for i in 0..16 {
                 colormesh {
@@ -239,7 +239,7 @@ pub fn root() -> Element {
 }
 
 pub fn setup(mut commands: Commands) {
-    commands.spawn((Transform::default(), Visibility::default(), SptsDioxusRootComponent(root)));
+    commands.spawn((Transform::default(), Visibility::default(), SptsDioxusRootComponent::new(root)));
 }
 
 pub fn update(button_state: Res<ButtonInput<KeyCode>>, mut state: ResMut<State>) {